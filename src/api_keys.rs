@@ -3,8 +3,14 @@ use std::sync::Arc;
 
 use reqwest::Method;
 
-use crate::types::{ApiKey, ApiKeyToken, CreateApiKeyOptions};
-use crate::{Config, Result};
+use crate::types::{ApiKey, ApiKeyToken, CreateApiKeyOptions, RotateResult};
+use crate::{
+    config::{decode, path},
+    Config, Result,
+};
+
+/// Maximum amount of characters in the name of an API key.
+pub const MAX_API_KEY_NAME_LENGTH: usize = 50;
 
 /// `Resend` APIs for `/api-keys` endpoints.
 #[derive(Clone)]
@@ -18,9 +24,11 @@ impl ApiKeysSvc {
     // Reasoning for allow: https://github.com/resend/resend-rust/pull/1#issuecomment-2081646115
     #[allow(clippy::needless_pass_by_value)]
     pub async fn create(&self, api_key: CreateApiKeyOptions) -> Result<ApiKeyToken> {
+        api_key.validate()?;
+
         let request = self.0.build(Method::POST, "/api-keys");
         let response = self.0.send(request.json(&api_key)).await?;
-        let content = response.json::<ApiKeyToken>().await?;
+        let content = decode::<ApiKeyToken>(response).await?;
 
         Ok(content)
     }
@@ -32,17 +40,33 @@ impl ApiKeysSvc {
     pub async fn list(&self) -> Result<Vec<ApiKey>> {
         let request = self.0.build(Method::GET, "/api-keys");
         let response = self.0.send(request).await?;
-        let content = response.json::<types::ListApiKeyResponse>().await?;
+        let content = decode::<types::ListApiKeyResponse>(response).await?;
 
         Ok(content.data)
     }
 
+    /// Replaces the API key `old_id` with a new one: creates the new key first and only deletes
+    /// the old one once that succeeded.
+    ///
+    /// The returned token is only ever shown once, persist it before discarding the response. It
+    /// is returned even if deleting the old key failed, see [`RotateResult::delete_error`].
+    #[maybe_async::maybe_async]
+    pub async fn rotate(&self, old_id: &str, api_key: CreateApiKeyOptions) -> Result<RotateResult> {
+        let token = self.create(api_key).await?;
+        let delete_error = self.delete(old_id).await.err();
+
+        Ok(RotateResult {
+            token,
+            delete_error,
+        })
+    }
+
     /// Remove an existing API key.
     ///
     /// <https://resend.com/docs/api-reference/api-keys/delete-api-key>
     #[maybe_async::maybe_async]
     pub async fn delete(&self, api_key_id: &str) -> Result<()> {
-        let path = format!("/api-keys/{api_key_id}");
+        let path = path("/api-keys", &[api_key_id]);
 
         let request = self.0.build(Method::DELETE, &path);
         let _response = self.0.send(request).await?;
@@ -58,12 +82,14 @@ impl fmt::Debug for ApiKeysSvc {
 }
 
 pub mod types {
-    use std::{fmt, ops::Deref};
+    use std::{convert::Infallible, fmt, ops::Deref, str::FromStr};
 
     use ecow::EcoString;
     use serde::{Deserialize, Serialize};
 
-    use crate::types::DomainId;
+    use super::MAX_API_KEY_NAME_LENGTH;
+    use crate::types::{DomainId, ValidationError};
+    use crate::Error;
 
     /// Unique [`ApiKey`] identifier.
     #[derive(Debug, Clone, Deserialize)]
@@ -93,6 +119,16 @@ pub mod types {
         }
     }
 
+    impl FromStr for ApiKeyId {
+        type Err = Infallible;
+
+        /// Same as [`ApiKeyId::new`], never fails.
+        #[inline]
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            Ok(Self::new(s))
+        }
+    }
+
     impl fmt::Display for ApiKeyId {
         fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
             fmt::Display::fmt(&self, f)
@@ -142,6 +178,13 @@ pub mod types {
             self
         }
 
+        /// Sets the permission of the API key.
+        #[inline]
+        pub const fn with_permission(mut self, permission: Permission) -> Self {
+            self.permission = Some(permission);
+            self
+        }
+
         /// Restricts an API key to send emails only from a specific domain.
         #[inline]
         pub fn with_domain_access(mut self, domain_id: &DomainId) -> Self {
@@ -149,6 +192,30 @@ pub mod types {
             self.domain_id = Some(domain_id.clone());
             self
         }
+
+        /// Checks that the name isn't blank or longer than [`MAX_API_KEY_NAME_LENGTH`] and that
+        /// the permission can be used with the domain restriction, this is done automatically
+        /// before creating the API key.
+        pub fn validate(&self) -> Result<(), ValidationError> {
+            if self.name.trim().is_empty() {
+                return Err(ValidationError::MissingField("name"));
+            }
+
+            let length = self.name.chars().count();
+            if length > MAX_API_KEY_NAME_LENGTH {
+                return Err(ValidationError::NameTooLong {
+                    length,
+                    max: MAX_API_KEY_NAME_LENGTH,
+                });
+            }
+
+            match (self.permission, &self.domain_id) {
+                (Some(Permission::FullAccess), Some(_)) => {
+                    Err(ValidationError::DomainRequiresSendingAccess)
+                }
+                _ => Ok(()),
+            }
+        }
     }
 
     /// Full or restricted access of the [`ApiKey`].
@@ -156,17 +223,29 @@ pub mod types {
     /// * `full_access` - Can create, delete, get, and update any resource.
     /// * `sending_access` - Can only send emails.
     #[must_use]
-    #[derive(Debug, Copy, Clone, Serialize)]
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+    #[serde(rename_all = "snake_case")]
     pub enum Permission {
-        #[serde(rename = "full_access")]
         FullAccess,
-        #[serde(rename = "sending_access")]
         SendingAccess,
     }
 
+    impl Permission {
+        /// Returns the permission an [`ApiKey`] needs with the given domain restriction.
+        ///
+        /// Restricting a key to a domain is only possible with [`Permission::SendingAccess`].
+        #[inline]
+        pub const fn for_domain(domain_id: Option<&DomainId>) -> Self {
+            match domain_id {
+                Some(_) => Self::SendingAccess,
+                None => Self::FullAccess,
+            }
+        }
+    }
+
     /// Token and ID of the newly created [`ApiKey`].
     #[must_use]
-    #[derive(Debug, Clone, Deserialize)]
+    #[derive(Clone, Deserialize)]
     pub struct ApiKeyToken {
         /// The ID of the API key.
         pub id: ApiKeyId,
@@ -174,6 +253,28 @@ pub mod types {
         pub token: String,
     }
 
+    impl fmt::Debug for ApiKeyToken {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            // Don't output the token.
+            f.debug_struct("ApiKeyToken")
+                .field("id", &self.id)
+                .field("token", &"re_*********")
+                .finish()
+        }
+    }
+
+    /// Outcome of [`ApiKeysSvc::rotate`].
+    ///
+    /// [`ApiKeysSvc::rotate`]: crate::services::ApiKeysSvc::rotate
+    #[must_use]
+    #[derive(Debug)]
+    pub struct RotateResult {
+        /// Token and ID of the new API key.
+        pub token: ApiKeyToken,
+        /// Why the old API key couldn't be deleted, in which case it is still valid.
+        pub delete_error: Option<Error>,
+    }
+
     #[must_use]
     #[derive(Debug, Clone, Deserialize)]
     pub struct ListApiKeyResponse {
@@ -196,9 +297,17 @@ pub mod types {
 
 #[cfg(test)]
 mod test {
-    use crate::tests::CLIENT;
-    use crate::types::CreateApiKeyOptions;
-    use crate::{Resend, Result};
+    use crate::limits::MAX_API_KEY_NAME_LENGTH;
+    #[cfg(not(feature = "blocking"))]
+    use crate::tests::{
+        mock::{MockResponse, MockServer},
+        CLIENT,
+    };
+    use crate::types::{
+        ApiKeyId, ApiKeyToken, CreateApiKeyOptions, DomainId, Permission, ValidationError,
+    };
+    #[cfg(not(feature = "blocking"))]
+    use crate::{Error, Resend, Result};
 
     #[tokio::test]
     #[cfg(not(feature = "blocking"))]
@@ -225,4 +334,139 @@ mod test {
 
         Ok(())
     }
+
+    #[tokio::test]
+    #[cfg(not(feature = "blocking"))]
+    async fn full_access_with_domain() -> Result<()> {
+        let server = MockServer::start(|_| MockResponse::json(200, r#"{"id":"1","token":"re_1"}"#));
+        let resend = Resend::builder("re_123").base_url(server.url()).build()?;
+
+        let domain_id = DomainId::new("4dd369bc-aa82-4ff3-97de-514ae3000ee0");
+        assert_eq!(
+            Permission::for_domain(Some(&domain_id)),
+            Permission::SendingAccess
+        );
+        assert_eq!(Permission::for_domain(None), Permission::FullAccess);
+
+        let api_key = CreateApiKeyOptions::new("Production")
+            .with_domain_access(&domain_id)
+            .with_full_access();
+        let result = resend.api_keys.create(api_key).await;
+
+        assert!(matches!(
+            result,
+            Err(Error::Validation(
+                ValidationError::DomainRequiresSendingAccess
+            ))
+        ));
+        assert!(server.requests().is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[cfg(not(feature = "blocking"))]
+    async fn rotate() -> Result<()> {
+        let server = MockServer::start(|request| match request.method.as_str() {
+            "POST" if request.body_str().contains("Invalid") => MockResponse::json(
+                422,
+                r#"{"statusCode":422,"message":"Invalid name.","name":"validation_error"}"#,
+            ),
+            "POST" => MockResponse::json(200, r#"{"id":"new","token":"re_new"}"#),
+            _ if request.path.ends_with("/broken") => MockResponse::json(
+                500,
+                r#"{"statusCode":500,"message":"","name":"internal_server_error"}"#,
+            ),
+            _ => MockResponse::json(200, "{}"),
+        });
+        let resend = Resend::builder("re_123").base_url(server.url()).build()?;
+
+        let rotated = resend
+            .api_keys
+            .rotate("old", CreateApiKeyOptions::new("Production"))
+            .await?;
+        assert_eq!(rotated.token.token, "re_new");
+        assert!(rotated.delete_error.is_none());
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].method, "POST");
+        assert_eq!(requests[1].method, "DELETE");
+        assert_eq!(requests[1].path, "/api-keys/old");
+
+        // The old key is kept if the new one can't be created.
+        let result = resend
+            .api_keys
+            .rotate("old", CreateApiKeyOptions::new("Invalid"))
+            .await;
+        assert!(matches!(result, Err(Error::Resend(_))));
+        assert_eq!(server.requests().len(), 3);
+
+        // The new token isn't lost if the old key can't be deleted.
+        let rotated = resend
+            .api_keys
+            .rotate("broken", CreateApiKeyOptions::new("Production"))
+            .await?;
+        assert_eq!(rotated.token.token, "re_new");
+        assert!(matches!(rotated.delete_error, Some(Error::Resend(_))));
+
+        Ok(())
+    }
+
+    #[test]
+    fn name_validation() {
+        let api_key =
+            CreateApiKeyOptions::new("Production").with_permission(Permission::FullAccess);
+        assert_eq!(api_key.permission, Some(Permission::FullAccess));
+        assert_eq!(api_key.validate(), Ok(()));
+
+        let name = "a".repeat(MAX_API_KEY_NAME_LENGTH);
+        assert_eq!(CreateApiKeyOptions::new(&name).validate(), Ok(()));
+
+        for name in ["", "  "] {
+            assert_eq!(
+                CreateApiKeyOptions::new(name).validate(),
+                Err(ValidationError::MissingField("name"))
+            );
+        }
+
+        let name = "a".repeat(MAX_API_KEY_NAME_LENGTH + 1);
+        assert_eq!(
+            CreateApiKeyOptions::new(&name).validate(),
+            Err(ValidationError::NameTooLong {
+                length: MAX_API_KEY_NAME_LENGTH + 1,
+                max: MAX_API_KEY_NAME_LENGTH,
+            })
+        );
+    }
+
+    #[test]
+    fn token_redacted() {
+        let token = ApiKeyToken {
+            id: ApiKeyId::new("dacf4072-4119-4d88-932f-6202748ac7c8"),
+            token: "re_c1tpEyD8_NKFusih9vKVQknRAQfmFcWCv".to_owned(),
+        };
+
+        let debug = format!("{token:?}");
+        assert!(debug.contains("dacf4072-4119-4d88-932f-6202748ac7c8"));
+        assert!(!debug.contains("re_c1tpEyD8_NKFusih9vKVQknRAQfmFcWCv"));
+    }
+
+    #[test]
+    fn wire_names() -> serde_json::Result<()> {
+        let full = CreateApiKeyOptions::new("Production").with_full_access();
+        let expected = serde_json::json!({ "name": "Production", "permission": "full_access" });
+        assert_eq!(serde_json::to_value(full)?, expected);
+
+        let domain = CreateApiKeyOptions::new("Production")
+            .with_domain_access(&DomainId::new("4dd369bc-aa82-4ff3-97de-514ae3000ee0"));
+        let expected = serde_json::json!({
+            "name": "Production",
+            "permission": "sending_access",
+            "domain_id": "4dd369bc-aa82-4ff3-97de-514ae3000ee0"
+        });
+        assert_eq!(serde_json::to_value(domain)?, expected);
+
+        Ok(())
+    }
 }
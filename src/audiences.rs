@@ -4,9 +4,10 @@ use std::sync::Arc;
 use reqwest::Method;
 
 use crate::types::Audience;
-use crate::{Config, Result};
-
-use self::types::CreateAudienceResponse;
+use crate::{
+    config::{decode, path},
+    Config, Result,
+};
 
 /// `Resend` APIs for `/audiences` endpoints.
 #[derive(Clone)]
@@ -15,18 +16,18 @@ pub struct AudiencesSvc(pub(crate) Arc<Config>);
 impl AudiencesSvc {
     /// Creates a new list of contacts.
     ///
-    /// Returns an `id` of a created audience.
+    /// Returns the created audience.
     ///
     /// <https://resend.com/docs/api-reference/audiences/create-audience>
     #[maybe_async::maybe_async]
-    pub async fn create(&self, name: &str) -> Result<CreateAudienceResponse> {
+    pub async fn create(&self, name: &str) -> Result<Audience> {
         let audience = types::CreateAudienceRequest {
             name: name.to_owned(),
         };
 
         let request = self.0.build(Method::POST, "/audiences");
         let response = self.0.send(request.json(&audience)).await?;
-        let content = response.json::<CreateAudienceResponse>().await?;
+        let content = decode::<Audience>(response).await?;
 
         Ok(content)
     }
@@ -36,27 +37,34 @@ impl AudiencesSvc {
     /// <https://resend.com/docs/api-reference/audiences/get-audience>
     #[maybe_async::maybe_async]
     pub async fn get(&self, id: &str) -> Result<Audience> {
-        let path = format!("/audiences/{id}");
+        let path = path("/audiences", &[id]);
 
         let request = self.0.build(Method::GET, &path);
         let response = self.0.send(request).await?;
-        let content = response.json::<Audience>().await?;
+        let content = decode::<Audience>(response).await?;
 
         Ok(content)
     }
 
     /// Removes an existing audience.
     ///
+    /// Idempotent: succeeds instead of failing if the audience doesn't exist (`404`), e.g. because
+    /// it was already deleted.
+    ///
     /// <https://resend.com/docs/api-reference/audiences/delete-audience>
     #[maybe_async::maybe_async]
-    pub async fn delete(&self, id: &str) -> Result<bool> {
-        let path = format!("/audiences/{id}");
+    pub async fn delete(&self, id: &str) -> Result<()> {
+        let path = path("/audiences", &[id]);
 
         let request = self.0.build(Method::DELETE, &path);
-        let response = self.0.send(request).await?;
-        let content = response.json::<types::RemoveAudienceResponse>().await?;
+        let response = match self.0.send(request).await {
+            Ok(response) => response,
+            Err(e) if e.is_not_found() => return Ok(()),
+            Err(e) => return Err(e),
+        };
+        let _content = decode::<types::RemoveAudienceResponse>(response).await?;
 
-        Ok(content.deleted)
+        Ok(())
     }
 
     /// Retrieves a list of audiences.
@@ -66,10 +74,28 @@ impl AudiencesSvc {
     pub async fn list(&self) -> Result<Vec<Audience>> {
         let request = self.0.build(Method::GET, "/audiences");
         let response = self.0.send(request).await?;
-        let content = response.json::<types::ListAudienceResponse>().await?;
+        let content = decode::<types::ListAudienceResponse>(response).await?;
 
         Ok(content.data)
     }
+
+    /// Returns the first audience called `name`, creating it if there is none.
+    ///
+    /// Handy for setup scripts that run on every deploy, as it doesn't create duplicates.
+    #[maybe_async::maybe_async]
+    pub async fn get_or_create_by_name(&self, name: &str) -> Result<Audience> {
+        let existing = self
+            .list()
+            .await?
+            .into_iter()
+            .find(|audience| audience.name == name);
+
+        if let Some(audience) = existing {
+            return Ok(audience);
+        }
+
+        self.create(name).await
+    }
 }
 
 impl fmt::Debug for AudiencesSvc {
@@ -79,7 +105,7 @@ impl fmt::Debug for AudiencesSvc {
 }
 
 pub mod types {
-    use std::{fmt, ops::Deref};
+    use std::{convert::Infallible, fmt, ops::Deref, str::FromStr};
 
     use ecow::EcoString;
     use serde::{Deserialize, Serialize};
@@ -95,6 +121,19 @@ pub mod types {
         pub fn new(id: &str) -> Self {
             Self(EcoString::from(id))
         }
+
+        /// Creates a new [`AudienceId`], checking that `id` is a valid UUID.
+        ///
+        /// Unlike [`AudienceId::new`], this catches obviously wrong ids before they reach the API.
+        #[cfg(feature = "uuid")]
+        pub fn try_new(id: &str) -> Result<Self, crate::types::IdParseError> {
+            uuid::Uuid::try_parse(id)
+                .map(|_| Self::new(id))
+                .map_err(|source| crate::types::IdParseError {
+                    id: id.to_owned(),
+                    source,
+                })
+        }
     }
 
     impl Deref for AudienceId {
@@ -112,6 +151,16 @@ pub mod types {
         }
     }
 
+    impl FromStr for AudienceId {
+        type Err = Infallible;
+
+        /// Same as [`AudienceId::new`], never fails.
+        #[inline]
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            Ok(Self::new(s))
+        }
+    }
+
     impl fmt::Display for AudienceId {
         fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
             fmt::Display::fmt(self.as_ref(), f)
@@ -125,14 +174,6 @@ pub mod types {
         pub name: String,
     }
 
-    #[derive(Debug, Clone, Deserialize)]
-    pub struct CreateAudienceResponse {
-        /// The ID of the audience.
-        pub id: AudienceId,
-        /// The name of the audience.
-        pub name: String,
-    }
-
     /// Name and ID of an existing contact list.
     #[must_use]
     #[derive(Debug, Clone, Deserialize)]
@@ -144,6 +185,11 @@ pub mod types {
         /// The name of the audience.
         pub name: String,
         /// The date that the object was created in ISO8601 format.
+        ///
+        /// Empty when returned by [`AudiencesSvc::create`], the API doesn't include it there.
+        ///
+        /// [`AudiencesSvc::create`]: crate::services::AudiencesSvc::create
+        #[serde(default)]
         pub created_at: String,
     }
 
@@ -153,6 +199,7 @@ pub mod types {
         #[allow(dead_code)]
         pub id: AudienceId,
         /// The deleted attribute indicates that the corresponding audience has been deleted.
+        #[allow(dead_code)]
         pub deleted: bool,
     }
 
@@ -166,7 +213,14 @@ pub mod types {
 
 #[cfg(test)]
 mod test {
-    use crate::tests::CLIENT;
+    use crate::audiences::types::CreateAudienceRequest;
+    #[cfg(not(feature = "blocking"))]
+    use crate::tests::{
+        mock::{MockResponse, MockServer},
+        CLIENT,
+    };
+    use crate::types::Audience;
+    #[cfg(not(feature = "blocking"))]
     use crate::{Resend, Result};
 
     #[tokio::test]
@@ -190,9 +244,119 @@ mod test {
         assert!(audiences_before > 1);
 
         // Delete.
-        let deleted = resend.audiences.delete(&id).await?;
-        assert!(deleted);
+        resend.audiences.delete(&id).await?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn wire_names() -> serde_json::Result<()> {
+        let audience = CreateAudienceRequest {
+            name: "Registered Users".to_owned(),
+        };
+        let expected = serde_json::json!({ "name": "Registered Users" });
+        assert_eq!(serde_json::to_value(audience)?, expected);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[cfg(not(feature = "blocking"))]
+    async fn get_or_create_by_name() -> Result<()> {
+        let server = MockServer::start(|request| match request.method.as_str() {
+            "GET" => MockResponse::json(
+                200,
+                r#"{"object":"list","data":[{
+                    "id":"78261eea-8f8b-4381-83c6-79fa7120f1cf",
+                    "name":"Registered Users",
+                    "created_at":"2023-10-06T22:59:55.977Z"
+                }]}"#,
+            ),
+            _ => MockResponse::json(
+                201,
+                r#"{"object":"audience","id":"d91cd9bd-1176-453e-8fc1-35364d380206","name":"Newsletter"}"#,
+            ),
+        });
+        let resend = Resend::builder("re_123").base_url(server.url()).build()?;
+
+        // Existing audience, nothing is created.
+        let audience = resend
+            .audiences
+            .get_or_create_by_name("Registered Users")
+            .await?;
+        assert_eq!(audience.id.as_ref(), "78261eea-8f8b-4381-83c6-79fa7120f1cf");
+        assert_eq!(server.requests().len(), 1);
+
+        // Missing audience.
+        let audience = resend.audiences.get_or_create_by_name("Newsletter").await?;
+        assert_eq!(audience.id.as_ref(), "d91cd9bd-1176-453e-8fc1-35364d380206");
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 3);
+        assert_eq!(requests[2].method, "POST");
+        assert_eq!(requests[2].body_str(), r#"{"name":"Newsletter"}"#);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[cfg(not(feature = "blocking"))]
+    async fn delete_missing() -> Result<()> {
+        let server = MockServer::start(|request| {
+            if request.path.ends_with("missing") {
+                let body =
+                    r#"{"statusCode":404,"message":"Audience not found","name":"not_found"}"#;
+                return MockResponse::json(404, body);
+            }
+            if request.path.ends_with("78261eea") {
+                let body = r#"{"object":"audience","id":"78261eea","deleted":true}"#;
+                return MockResponse::json(200, body);
+            }
+            let body = r#"{"statusCode":500,"message":"","name":"internal_server_error"}"#;
+            MockResponse::json(500, body)
+        });
+        let resend = Resend::builder("re_123").base_url(server.url()).build()?;
+
+        resend.audiences.delete("78261eea").await?;
+        resend.audiences.delete("missing").await?;
+        assert!(resend.audiences.delete("broken").await.is_err());
 
         Ok(())
     }
+
+    #[test]
+    fn create_response() -> serde_json::Result<()> {
+        let response = r#"{
+            "object": "audience",
+            "id": "78261eea-8f8b-4381-83c6-79fa7120f1cf",
+            "name": "Registered Users"
+        }"#;
+        let audience = serde_json::from_str::<Audience>(response)?;
+        assert_eq!(audience.id.as_ref(), "78261eea-8f8b-4381-83c6-79fa7120f1cf");
+        assert_eq!(audience.name, "Registered Users");
+        assert!(audience.created_at.is_empty());
+
+        let response = r#"{
+            "object": "audience",
+            "id": "78261eea-8f8b-4381-83c6-79fa7120f1cf",
+            "name": "Registered Users",
+            "created_at": "2023-10-06T22:59:55.977Z"
+        }"#;
+        let audience = serde_json::from_str::<Audience>(response)?;
+        assert_eq!(audience.created_at, "2023-10-06T22:59:55.977Z");
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "uuid")]
+    fn try_new_id() {
+        use crate::types::AudienceId;
+
+        let id = AudienceId::try_new("78261eea-8f8b-4381-83c6-79fa7120f1cf");
+        assert!(id.is_ok());
+
+        let error = AudienceId::try_new("78261eea-8f8b-4381");
+        assert!(error.is_err_and(|e| e.id == "78261eea-8f8b-4381"));
+    }
 }
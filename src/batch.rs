@@ -1,34 +1,291 @@
+use std::borrow::Cow;
 use std::sync::Arc;
 
 use reqwest::Method;
 
 use crate::{
+    config::decode,
     emails::types::{CreateEmailBaseOptions, CreateEmailResponse, SendEmailBatchResponse},
-    Config, Result,
+    Config, Error, Result,
 };
 
+/// Maximum amount of emails accepted by a single `/emails/batch` request.
+pub const MAX_BATCH_SIZE: usize = 100;
+
 /// `Resend` APIs for `/emails` endpoints.
 #[derive(Clone, Debug)]
 pub struct BatchSvc(pub(crate) Arc<Config>);
 
 impl BatchSvc {
-    /// Trigger up to 100 batch emails at once.
+    /// Trigger batch emails, at most 100 per request.
     ///
     /// Instead of sending one email per HTTP request, we provide a batching endpoint
     /// that permits you to send up to 100 emails in a single API call.
     ///
+    /// Larger inputs are split into multiple requests of up to 100 emails, sent one after
+    /// another through the rate limiter. The returned ids keep the order of the input emails.
+    /// All emails are validated before the first request is made, but a failed request stops the
+    /// remaining ones from being sent while earlier ones will have already been delivered.
+    ///
     /// <https://resend.com/docs/api-reference/emails/send-batch-emails>
     #[maybe_async::maybe_async]
     pub async fn send<T>(&self, emails: T) -> Result<Vec<CreateEmailResponse>>
     where
         T: IntoIterator<Item = CreateEmailBaseOptions> + Send,
     {
-        let emails: Vec<_> = emails.into_iter().collect();
+        let mut emails: Vec<_> = emails.into_iter().collect();
+        for email in &mut emails {
+            email.validate()?;
+
+            if let Cow::Owned(merged) = email.with_default_tags(&self.0.default_tags) {
+                *email = merged;
+            }
+        }
+
+        self.send_chunks(&emails).await
+    }
+
+    /// Trigger batch emails like [`BatchSvc::send`], pairing every result with the index of its
+    /// email in `emails`.
+    ///
+    /// Emails that fail validation aren't sent, and get their [`Error::Validation`] instead of
+    /// failing the whole batch. The other emails are sent in requests of up to 100 emails, a
+    /// failed request doesn't stop the next ones: every email of that request gets its error as
+    /// an [`Error::Batch`], and the emails of the other requests keep their ids.
+    ///
+    /// <https://resend.com/docs/api-reference/emails/send-batch-emails>
+    #[maybe_async::maybe_async]
+    pub async fn send_indexed<T>(&self, emails: T) -> Vec<(usize, Result<CreateEmailResponse>)>
+    where
+        T: IntoIterator<Item = CreateEmailBaseOptions> + Send,
+    {
+        let mut results = Vec::new();
+        let mut indices = Vec::new();
+        let mut valid = Vec::new();
+        for (index, mut email) in emails.into_iter().enumerate() {
+            if let Err(error) = email.validate() {
+                results.push((index, Err(error.into())));
+                continue;
+            }
+
+            if let Cow::Owned(merged) = email.with_default_tags(&self.0.default_tags) {
+                email = merged;
+            }
+            indices.push(index);
+            valid.push(email);
+        }
 
+        let chunks = indices
+            .chunks(MAX_BATCH_SIZE)
+            .zip(valid.chunks(MAX_BATCH_SIZE));
+        for (indices, chunk) in chunks {
+            match self.send_chunk(chunk).await {
+                Ok(ids) => results.extend(indices.iter().copied().zip(ids.into_iter().map(Ok))),
+                Err(error) => {
+                    let error = Arc::new(error);
+                    let failed = indices
+                        .iter()
+                        .map(|index| (*index, Err(Error::Batch(error.clone()))));
+                    results.extend(failed);
+                }
+            }
+        }
+        results.sort_unstable_by_key(|(index, _)| *index);
+
+        results
+    }
+
+    /// Sends already validated `emails`, in requests of up to [`MAX_BATCH_SIZE`] emails.
+    #[maybe_async::maybe_async]
+    async fn send_chunks(
+        &self,
+        emails: &[CreateEmailBaseOptions],
+    ) -> Result<Vec<CreateEmailResponse>> {
+        let mut ids = Vec::with_capacity(emails.len());
+        for chunk in emails.chunks(MAX_BATCH_SIZE) {
+            ids.extend(self.send_chunk(chunk).await?);
+        }
+
+        Ok(ids)
+    }
+
+    /// Sends a single request of already validated `emails`.
+    ///
+    /// Fails with [`Error::Parse`] if the API doesn't return one id per email, as the ids
+    /// couldn't be matched to their emails.
+    #[maybe_async::maybe_async]
+    async fn send_chunk(
+        &self,
+        emails: &[CreateEmailBaseOptions],
+    ) -> Result<Vec<CreateEmailResponse>> {
         let request = self.0.build(Method::POST, "/emails/batch");
-        let response = self.0.send(request.json(&emails)).await?;
-        let content = response.json::<SendEmailBatchResponse>().await?;
+        let response = self.0.send(request.json(emails)).await?;
+        let content = decode::<SendEmailBatchResponse>(response).await?;
+
+        if content.data.len() != emails.len() {
+            return Err(Error::Parse(format!(
+                "expected {} ids for the batch, received {}",
+                emails.len(),
+                content.data.len()
+            )));
+        }
 
         Ok(content.data)
     }
 }
+
+#[cfg(all(test, not(feature = "blocking")))]
+mod test {
+    use crate::tests::mock::{MockResponse, MockServer};
+    use crate::types::{CreateEmailBaseOptions, ValidationError};
+    use crate::{Error, Resend, Result};
+
+    #[tokio::test]
+    async fn splits_oversized_batches() -> Result<()> {
+        // Responds with the subject of every email as its id.
+        let server = MockServer::start(|request| {
+            let emails: Vec<serde_json::Value> =
+                serde_json::from_slice(&request.body).unwrap_or_default();
+            let data: Vec<_> = emails
+                .iter()
+                .map(|email| serde_json::json!({ "id": email["subject"] }))
+                .collect();
+
+            MockResponse::json(200, &serde_json::json!({ "data": data }).to_string())
+        });
+        let resend = Resend::builder("re_123").base_url(server.url()).build()?;
+
+        let emails = (0..250)
+            .map(|i| CreateEmailBaseOptions::new("from@a.dev", ["to@a.dev"], i.to_string()));
+        let ids = resend.batch.send(emails).await?;
+
+        let sizes: Vec<_> = server
+            .requests()
+            .iter()
+            .map(|request| {
+                let emails: Vec<serde_json::Value> =
+                    serde_json::from_slice(&request.body).unwrap_or_default();
+                emails.len()
+            })
+            .collect();
+        assert_eq!(sizes, [100, 100, 50]);
+
+        assert_eq!(ids.len(), 250);
+        for (i, id) in ids.iter().enumerate() {
+            assert_eq!(id.id.as_ref(), i.to_string());
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn mixed_scheduled_and_immediate() -> Result<()> {
+        let server =
+            MockServer::start(|_| MockResponse::json(200, r#"{"data":[{"id":"1"},{"id":"2"}]}"#));
+        let resend = Resend::builder("re_123").base_url(server.url()).build()?;
+
+        let emails = [
+            CreateEmailBaseOptions::new("from@a.dev", ["to@a.dev"], "Now"),
+            CreateEmailBaseOptions::new("from@a.dev", ["to@a.dev"], "Later")
+                .with_scheduled_at("2024-08-05T11:52:01.858Z"),
+        ];
+        let _ = resend.batch.send(emails).await?;
+
+        let requests = server.requests();
+        let body: serde_json::Value = serde_json::from_slice(&requests[0].body).unwrap_or_default();
+        assert!(body[0].get("scheduled_at").is_none());
+        assert_eq!(body[1]["scheduled_at"], "2024-08-05T11:52:01.858Z");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn send_indexed() -> Result<()> {
+        let server = MockServer::start(|request| {
+            let emails: Vec<serde_json::Value> =
+                serde_json::from_slice(&request.body).unwrap_or_default();
+            let data: Vec<_> = emails
+                .iter()
+                .map(|email| serde_json::json!({ "id": email["subject"] }))
+                .collect();
+
+            MockResponse::json(200, &serde_json::json!({ "data": data }).to_string())
+        });
+        let resend = Resend::builder("re_123").base_url(server.url()).build()?;
+
+        let emails = ["a", "", "c"]
+            .map(|subject| CreateEmailBaseOptions::new("from@a.dev", ["to@a.dev"], subject));
+        let results = resend.batch.send_indexed(emails).await;
+
+        assert_eq!(results.len(), 3);
+        assert!(matches!(&results[0], (0, Ok(response)) if response.id.as_ref() == "a"));
+        assert!(matches!(
+            &results[1],
+            (
+                1,
+                Err(Error::Validation(ValidationError::MissingField("subject")))
+            )
+        ));
+        assert!(matches!(&results[2], (2, Ok(response)) if response.id.as_ref() == "c"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn send_indexed_missing_ids() -> Result<()> {
+        let server = MockServer::start(|_| MockResponse::json(200, r#"{"data":[{"id":"a"}]}"#));
+        let resend = Resend::builder("re_123").base_url(server.url()).build()?;
+
+        let emails = ["a", "b"]
+            .map(|subject| CreateEmailBaseOptions::new("from@a.dev", ["to@a.dev"], subject));
+        let results = resend.batch.send_indexed(emails).await;
+        assert_eq!(results.len(), 2);
+        for (_, result) in &results {
+            let Err(Error::Batch(error)) = result else {
+                panic!("expected a batch error, got {result:?}");
+            };
+            assert!(matches!(**error, Error::Parse(_)));
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn send_indexed_failed_chunk() -> Result<()> {
+        // Fails the second request, of the last 50 emails.
+        let server = MockServer::start(|request| {
+            let emails: Vec<serde_json::Value> =
+                serde_json::from_slice(&request.body).unwrap_or_default();
+            if emails.len() == 50 {
+                let body = r#"{"statusCode":500,"message":"","name":"internal_server_error"}"#;
+                return MockResponse::json(500, body);
+            }
+            let data: Vec<_> = emails
+                .iter()
+                .map(|email| serde_json::json!({ "id": email["subject"] }))
+                .collect();
+
+            MockResponse::json(200, &serde_json::json!({ "data": data }).to_string())
+        });
+        let resend = Resend::builder("re_123").base_url(server.url()).build()?;
+
+        let emails = (0..150)
+            .map(|i| CreateEmailBaseOptions::new("from@a.dev", ["to@a.dev"], i.to_string()));
+        let results = resend.batch.send_indexed(emails).await;
+
+        assert_eq!(results.len(), 150);
+        for (i, (index, result)) in results.iter().enumerate().take(100) {
+            assert_eq!(*index, i);
+            assert!(matches!(result, Ok(response) if response.id.as_ref() == i.to_string()));
+        }
+        for (i, (index, result)) in results.iter().enumerate().skip(100) {
+            assert_eq!(*index, i);
+            let Err(Error::Batch(error)) = result else {
+                panic!("expected a batch error, got {result:?}");
+            };
+            assert!(matches!(&**error, Error::Resend(e) if e.status_code == 500));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,560 @@
+use std::fmt;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+#[cfg(feature = "blocking")]
+use reqwest::blocking::ClientBuilder as ReqwestClientBuilder;
+use reqwest::header::{HeaderMap, HeaderName};
+#[cfg(any(feature = "native-tls", feature = "rustls-tls"))]
+use reqwest::Certificate;
+#[cfg(not(feature = "blocking"))]
+use reqwest::ClientBuilder as ReqwestClientBuilder;
+use reqwest::{redirect::Policy, Url};
+
+#[cfg(feature = "circuit-breaker")]
+use crate::circuit_breaker::CircuitBreaker;
+use crate::config::{env_timeout, timeout_from, AuthHeader, SecretString};
+use crate::types::{RateLimitHandle, Tag};
+use crate::{Config, Resend, Result};
+
+/// Builder for a [`Resend`] client that configures the underlying [`reqwest::Client`].
+///
+/// Created with [`Resend::builder`].
+///
+/// [`reqwest::Client`]: reqwest::Client
+#[must_use]
+pub struct ResendBuilder {
+    api_key: SecretString,
+    base_url: Option<Url>,
+    max_body_size: Option<usize>,
+    default_tags: Vec<Tag>,
+    default_headers: HeaderMap,
+    auto_idempotency: bool,
+    #[cfg(feature = "tracing")]
+    warn_on_missing_text: bool,
+    rate_limit: bool,
+    auth_header: Option<AuthHeader>,
+    #[cfg(feature = "circuit-breaker")]
+    circuit_breaker: Option<(u32, Duration)>,
+    timeout: Option<Duration>,
+    /// Value of `RESEND_TIMEOUT_MS`, only parsed in [`ResendBuilder::build`] to return an error.
+    env_timeout: Option<String>,
+    client: ReqwestClientBuilder,
+}
+
+impl ResendBuilder {
+    /// Creates a new [`ResendBuilder`].
+    pub(crate) fn new(api_key: &str) -> Self {
+        Self {
+            api_key: SecretString::from(api_key),
+            base_url: None,
+            max_body_size: None,
+            default_tags: Vec::new(),
+            default_headers: HeaderMap::new(),
+            auto_idempotency: false,
+            #[cfg(feature = "tracing")]
+            warn_on_missing_text: false,
+            rate_limit: true,
+            auth_header: None,
+            #[cfg(feature = "circuit-breaker")]
+            circuit_breaker: None,
+            timeout: None,
+            env_timeout: env_timeout(),
+            client: ReqwestClientBuilder::new(),
+        }
+    }
+
+    /// Overrides the `base URL`, taking precedence over the `RESEND_BASE_URL` environment variable.
+    #[inline]
+    pub fn base_url(mut self, base_url: Url) -> Self {
+        self.base_url = Some(base_url);
+        self
+    }
+
+    /// Sets tags added to every sent email, e.g. `environment=prod`.
+    ///
+    /// Tags set on an email take precedence over default tags with the same name.
+    #[inline]
+    pub fn default_tags(mut self, tags: Vec<Tag>) -> Self {
+        self.default_tags = tags;
+        self
+    }
+
+    /// Sets headers sent with every request, e.g. to correlate traces or route through a proxy.
+    ///
+    /// The `Authorization` and `User-Agent` headers set by this crate take precedence over
+    /// default headers with the same name.
+    #[inline]
+    pub fn default_headers(mut self, headers: HeaderMap) -> Self {
+        self.default_headers = headers;
+        self
+    }
+
+    /// Derives the `Idempotency-Key` of every sent email from a hash of its content, so that
+    /// Resend deduplicates accidental repeated sends of the same email.
+    ///
+    /// Disabled by default. The hash is stable across processes and Rust releases, so sending the
+    /// exact same email twice on purpose requires a difference such as a header.
+    #[inline]
+    pub const fn auto_idempotency(mut self, enabled: bool) -> Self {
+        self.auto_idempotency = enabled;
+        self
+    }
+
+    /// Emits a `tracing` warning when sending an email with an HTML version but no text version,
+    /// which hurts deliverability.
+    ///
+    /// Disabled by default.
+    #[inline]
+    #[cfg(feature = "tracing")]
+    pub const fn warn_on_missing_text(mut self, enabled: bool) -> Self {
+        self.warn_on_missing_text = enabled;
+        self
+    }
+
+    /// Whether requests wait for the client-side rate limit configured by `RESEND_RATE_LIMIT`.
+    ///
+    /// Enabled by default. Only disable it if requests are already rate limited elsewhere, e.g.
+    /// by a proxy, otherwise bursts fail with `429` responses.
+    #[inline]
+    pub const fn rate_limit(mut self, enabled: bool) -> Self {
+        self.rate_limit = enabled;
+        self
+    }
+
+    /// Sends the API key in the `name` header, formatted by `format`, instead of
+    /// `Authorization: Bearer <api key>`.
+    ///
+    /// Only useful behind proxies that use the `Authorization` header themselves and forward the
+    /// API key to Resend from another header.
+    #[inline]
+    pub fn auth_header(
+        mut self,
+        name: HeaderName,
+        format: impl Fn(&str) -> String + Send + Sync + 'static,
+    ) -> Self {
+        let format = Arc::new(format);
+        self.auth_header = Some(AuthHeader { name, format });
+        self
+    }
+
+    /// Stops sending requests for `cooldown` after `threshold` consecutive failures, failing
+    /// them with [`Error::CircuitOpen`] instead.
+    ///
+    /// Failures are server errors (`5xx`), timeouts and connection failures. After the cooldown,
+    /// a single request is let through to check whether the API has recovered.
+    ///
+    /// [`Error::CircuitOpen`]: crate::Error::CircuitOpen
+    #[inline]
+    #[cfg(feature = "circuit-breaker")]
+    pub const fn circuit_breaker(mut self, threshold: u32, cooldown: Duration) -> Self {
+        self.circuit_breaker = Some((threshold, cooldown));
+        self
+    }
+
+    /// Sets the maximum size of a serialized request body, in bytes.
+    ///
+    /// Larger requests fail with [`ValidationError::BodyTooLarge`] before being sent, instead of
+    /// being rejected by the API after a long upload. Defaults to about 55 MiB, enough for the
+    /// [`MAX_ATTACHMENT_BYTES`] the API accepts once base64 encoded.
+    ///
+    /// [`MAX_ATTACHMENT_BYTES`]: crate::limits::MAX_ATTACHMENT_BYTES
+    ///
+    /// [`ValidationError::BodyTooLarge`]: crate::types::ValidationError::BodyTooLarge
+    #[inline]
+    pub const fn max_body_size(mut self, max_body_size: usize) -> Self {
+        self.max_body_size = Some(max_body_size);
+        self
+    }
+
+    /// Sets the redirect [`Policy`] of the underlying client.
+    ///
+    /// Redirects are followed up to 10 times by default, use [`Policy::none`] to disable them.
+    #[inline]
+    pub fn redirect(mut self, policy: Policy) -> Self {
+        self.client = self.client.redirect(policy);
+        self
+    }
+
+    /// Sets a timeout for each request, from connecting until the response body is read.
+    ///
+    /// Defaults to the `RESEND_TIMEOUT_MS` environment variable if set, otherwise to no timeout
+    /// for the async client and 30 seconds for the `blocking` one.
+    #[inline]
+    pub const fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Adds a custom root [`Certificate`] to the underlying client.
+    ///
+    /// Useful to trust the certificate of a proxy Resend is routed through.
+    #[inline]
+    #[cfg(any(feature = "native-tls", feature = "rustls-tls"))]
+    pub fn add_root_certificate(mut self, certificate: Certificate) -> Self {
+        self.client = self.client.add_root_certificate(certificate);
+        self
+    }
+
+    /// Controls the use of certificate validation.
+    ///
+    /// ### Warning
+    ///
+    /// **Dangerous**, accepting invalid certificates makes the client trust *any* certificate,
+    /// including expired ones and ones for other hosts. This opens up man-in-the-middle attacks
+    /// that can leak your API key. Only use it as a last resort, prefer
+    /// [`ResendBuilder::add_root_certificate`] instead.
+    #[inline]
+    #[cfg(any(feature = "native-tls", feature = "rustls-tls"))]
+    pub fn danger_accept_invalid_certs(mut self, accept: bool) -> Self {
+        self.client = self.client.danger_accept_invalid_certs(accept);
+        self
+    }
+
+    /// Resolves `domain` to `addr` instead of going through DNS, e.g. to pin the API host to an
+    /// internal gateway in egress-controlled environments.
+    ///
+    /// The port of `addr` is ignored, the port of the `base URL` is used.
+    #[inline]
+    pub fn resolve(mut self, domain: &str, addr: SocketAddr) -> Self {
+        self.client = self.client.resolve(domain, addr);
+        self
+    }
+
+    /// Only uses HTTP/1.
+    #[inline]
+    pub fn http1_only(mut self) -> Self {
+        self.client = self.client.http1_only();
+        self
+    }
+
+    /// Only uses HTTP/2, without negotiating the protocol first.
+    ///
+    /// Saves a round trip per connection when sending many emails, but fails against servers
+    /// (or proxies) that don't speak HTTP/2.
+    #[inline]
+    #[cfg(feature = "http2")]
+    pub fn http2_prior_knowledge(mut self) -> Self {
+        self.client = self.client.http2_prior_knowledge();
+        self
+    }
+
+    /// Creates the [`Resend`] client.
+    ///
+    /// Fails if the underlying client can't be built, or if the environment variable
+    /// `RESEND_TIMEOUT_MS` is set but is not a valid amount of milliseconds.
+    ///
+    /// ### Panics
+    ///
+    /// - Panics if the environment variable `RESEND_BASE_URL` is set but is not a valid `URL`.
+    pub fn build(self) -> Result<Resend> {
+        let env_timeout = self.env_timeout.as_deref().map(timeout_from).transpose()?;
+        let client = match self.timeout.or(env_timeout) {
+            Some(timeout) => self.client.timeout(timeout),
+            None => self.client,
+        };
+        let client = client.build()?;
+        let mut config = Config::new(self.api_key, client);
+
+        if let Some(base_url) = self.base_url {
+            config.base_url = base_url;
+        }
+        if let Some(max_body_size) = self.max_body_size {
+            config.max_body_size = max_body_size;
+        }
+        config.default_tags = self.default_tags;
+        config.default_headers = self.default_headers;
+        config.auto_idempotency = self.auto_idempotency;
+        #[cfg(feature = "tracing")]
+        {
+            config.warn_on_missing_text = self.warn_on_missing_text;
+        }
+        config.auth_header = self.auth_header;
+        if !self.rate_limit {
+            config.limiter = RateLimitHandle::unlimited();
+            config.priority_limiter = RateLimitHandle::unlimited();
+        }
+        #[cfg(feature = "circuit-breaker")]
+        if let Some((threshold, cooldown)) = self.circuit_breaker {
+            let breaker = CircuitBreaker::new(threshold, cooldown);
+            config.circuit_breaker = Some(Arc::new(breaker));
+        }
+
+        Ok(Resend::from_config(config))
+    }
+}
+
+impl fmt::Debug for ResendBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Don't output API key.
+        f.debug_struct("ResendBuilder")
+            .field("api_key", &self.api_key)
+            .field("base_url", &self.base_url.as_ref().map(Url::as_str))
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::PoisonError;
+
+    #[cfg(not(feature = "blocking"))]
+    use reqwest::redirect::Policy;
+
+    use super::ResendBuilder;
+    #[cfg(not(feature = "blocking"))]
+    use crate::tests::mock::{MockResponse, MockServer};
+    use crate::tests::ENV;
+    use crate::types::ValidationError;
+    #[cfg(not(feature = "blocking"))]
+    use crate::types::{Attachment, CreateEmailBaseOptions, Tag};
+    #[cfg(not(feature = "blocking"))]
+    use crate::Result;
+    use crate::{Error, Resend};
+
+    /// Returns a builder created while `RESEND_TIMEOUT_MS` is set to `value`.
+    fn builder_with_env_timeout(value: &str) -> ResendBuilder {
+        let _lock = ENV.lock().unwrap_or_else(PoisonError::into_inner);
+        std::env::set_var("RESEND_TIMEOUT_MS", value);
+        let builder = Resend::builder("re_123");
+        std::env::remove_var("RESEND_TIMEOUT_MS");
+
+        builder
+    }
+
+    #[test]
+    fn invalid_env_timeout() {
+        let result = builder_with_env_timeout("5s").build();
+        assert!(matches!(
+            result,
+            Err(Error::Validation(ValidationError::InvalidEnvVar {
+                name: "RESEND_TIMEOUT_MS",
+                ..
+            }))
+        ));
+    }
+
+    #[tokio::test]
+    #[cfg(not(feature = "blocking"))]
+    async fn env_timeout() -> Result<()> {
+        use std::time::Duration;
+
+        let server = MockServer::start(|_| {
+            std::thread::sleep(Duration::from_secs(2));
+            MockResponse::json(200, r#"{"id":"1"}"#)
+        });
+        let resend = builder_with_env_timeout("500")
+            .base_url(server.url())
+            .build()?;
+
+        let email = CreateEmailBaseOptions::new("from@a.dev", ["to@a.dev"], "Subject");
+        let result = resend.emails.send(email).await;
+        assert!(matches!(result, Err(Error::Http(e)) if e.is_timeout()));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[cfg(not(feature = "blocking"))]
+    async fn custom_redirect_policy() -> Result<()> {
+        let server = MockServer::start(|_| MockResponse::json(200, r#"{"id":"1"}"#));
+        let resend = Resend::builder("re_123")
+            .base_url(server.url())
+            .redirect(Policy::none())
+            .build()?;
+
+        let email = CreateEmailBaseOptions::new("from@a.dev", ["to@a.dev"], "Subject");
+        let id = resend.emails.send(email).await?.id;
+
+        assert_eq!(id.as_ref(), "1");
+        let requests = server.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].method, "POST");
+        assert_eq!(requests[0].path, "/emails");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[cfg(not(feature = "blocking"))]
+    async fn http1_only() -> Result<()> {
+        let server = MockServer::start(|_| MockResponse::json(200, r#"{"id":"1"}"#));
+        let resend = Resend::builder("re_123")
+            .base_url(server.url())
+            .http1_only()
+            .build()?;
+
+        let email = CreateEmailBaseOptions::new("from@a.dev", ["to@a.dev"], "Subject");
+        let _ = resend.emails.send(email).await?;
+        assert_eq!(server.requests().len(), 1);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[cfg(not(feature = "blocking"))]
+    async fn auth_header() -> Result<()> {
+        use reqwest::header::HeaderName;
+
+        let server = MockServer::start(|_| MockResponse::json(200, r#"{"id":"1"}"#));
+        let email = CreateEmailBaseOptions::new("from@a.dev", ["to@a.dev"], "Subject");
+
+        let resend = Resend::builder("re_123").base_url(server.url()).build()?;
+        let _ = resend.emails.send(email.clone()).await?;
+
+        let resend = Resend::builder("re_123")
+            .base_url(server.url())
+            .auth_header(HeaderName::from_static("x-resend-auth"), |key| {
+                format!("Key {key}")
+            })
+            .build()?;
+        let _ = resend.emails.send(email).await?;
+
+        let requests = server.requests();
+        assert_eq!(requests[0].header("authorization"), Some("Bearer re_123"));
+        assert_eq!(requests[1].header("authorization"), None);
+        assert_eq!(requests[1].header("x-resend-auth"), Some("Key re_123"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[cfg(not(feature = "blocking"))]
+    async fn default_tags() -> Result<()> {
+        let server = MockServer::start(|_| MockResponse::json(200, r#"{"id":"1"}"#));
+        let resend = Resend::builder("re_123")
+            .base_url(server.url())
+            .default_tags(vec![Tag::new("environment", "prod")])
+            .build()?;
+
+        let email = CreateEmailBaseOptions::new("from@a.dev", ["to@a.dev"], "Subject");
+        let _ = resend.emails.send(email.clone()).await?;
+        let email = email.with_tag(Tag::new("environment", "staging"));
+        let _ = resend.emails.send(email).await?;
+
+        let tags = server
+            .requests()
+            .iter()
+            .map(|request| {
+                let body = serde_json::from_slice::<serde_json::Value>(&request.body);
+                body.unwrap_or_default()["tags"].clone()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            tags,
+            [
+                serde_json::json!([{ "name": "environment", "value": "prod" }]),
+                serde_json::json!([{ "name": "environment", "value": "staging" }]),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[cfg(not(feature = "blocking"))]
+    async fn default_headers() -> Result<()> {
+        use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION};
+
+        let server = MockServer::start(|_| {
+            MockResponse::json(404, r#"{"statusCode":404,"message":"","name":"not_found"}"#)
+        });
+
+        let mut headers = HeaderMap::new();
+        let _ = headers.insert("x-trace-id", HeaderValue::from_static("abc123"));
+        let _ = headers.insert(AUTHORIZATION, HeaderValue::from_static("Bearer other"));
+        let resend = Resend::builder("re_123")
+            .base_url(server.url())
+            .default_headers(headers)
+            .build()?;
+        let _ = resend.emails.try_get("49a3999c").await?;
+
+        let requests = server.requests();
+        assert_eq!(requests[0].header("x-trace-id"), Some("abc123"));
+        assert_eq!(requests[0].header("authorization"), Some("Bearer re_123"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[cfg(not(feature = "blocking"))]
+    async fn max_body_size() -> Result<()> {
+        let server = MockServer::start(|_| MockResponse::json(200, r#"{"id":"1"}"#));
+        let resend = Resend::builder("re_123")
+            .base_url(server.url())
+            .max_body_size(1024)
+            .build()?;
+
+        let email = CreateEmailBaseOptions::new("from@a.dev", ["to@a.dev"], "Subject")
+            .with_text(&"a".repeat(2048));
+        let result = resend.emails.send(email).await;
+
+        assert!(matches!(
+            result,
+            Err(Error::Validation(ValidationError::BodyTooLarge {
+                max: 1024,
+                ..
+            }))
+        ));
+        assert!(server.requests().is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[cfg(not(feature = "blocking"))]
+    async fn default_max_body_size_fits_attachments() -> Result<()> {
+        let server = MockServer::start(|_| MockResponse::json(200, r#"{"id":"1"}"#));
+        let resend = Resend::builder("re_123").base_url(server.url()).build()?;
+
+        let attachment = Attachment::from_content(vec![0xff; 15 * 1024 * 1024]);
+        let email = CreateEmailBaseOptions::new("from@a.dev", ["to@a.dev"], "Subject")
+            .with_attachment(attachment);
+        let _ = resend.emails.send(email).await?;
+
+        // Sent base64 encoded, a third larger than the attachment.
+        let requests = server.requests();
+        assert!(requests[0].body.len() < 21 * 1024 * 1024);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[cfg(not(feature = "blocking"))]
+    async fn resolve() -> Result<()> {
+        use std::net::{Ipv4Addr, SocketAddr};
+
+        let server = MockServer::start(|_| MockResponse::json(200, r#"{"id":"1"}"#));
+        let port = server.url().port().unwrap_or_default();
+        let base_url = format!("http://resend.internal:{port}");
+
+        let resend = Resend::builder("re_123")
+            .base_url(base_url.parse().expect("should be a valid URL"))
+            .resolve(
+                "resend.internal",
+                SocketAddr::from((Ipv4Addr::LOCALHOST, 0)),
+            )
+            .build()?;
+
+        let email = CreateEmailBaseOptions::new("from@a.dev", ["to@a.dev"], "Subject");
+        let _ = resend.emails.send(email).await?;
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(
+            requests[0].header("host"),
+            Some(format!("resend.internal:{port}").as_str())
+        );
+
+        Ok(())
+    }
+
+    // The mock server only speaks HTTP/1, so this only checks that the client builds.
+    #[test]
+    #[cfg(feature = "http2")]
+    fn http2_prior_knowledge() {
+        let resend = Resend::builder("re_123").http2_prior_knowledge().build();
+        assert!(resend.is_ok());
+    }
+}
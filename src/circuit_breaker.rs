@@ -0,0 +1,184 @@
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "blocking")]
+use reqwest::blocking::Response;
+#[cfg(not(feature = "blocking"))]
+use reqwest::Response;
+
+use crate::{Error, Result};
+
+/// Stops sending requests after `threshold` consecutive failures, until `cooldown` has elapsed.
+///
+/// Once the cooldown has elapsed a single request is let through: if it gets a response the breaker
+/// closes again, otherwise it stays open for another cooldown. Requests failing before they reach
+/// the API, e.g. on validation, are not counted either way.
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    threshold: u32,
+    cooldown: Duration,
+    state: Mutex<State>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    failures: u32,
+    opened_at: Option<Instant>,
+}
+
+impl CircuitBreaker {
+    /// Creates a new, closed [`CircuitBreaker`].
+    pub const fn new(threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            threshold,
+            cooldown,
+            state: Mutex::new(State {
+                failures: 0,
+                opened_at: None,
+            }),
+        }
+    }
+
+    /// Fails with [`Error::CircuitOpen`] if no request should be sent right now.
+    pub fn check(&self) -> Result<()> {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+
+        match state.opened_at {
+            Some(opened_at) if opened_at.elapsed() < self.cooldown => Err(Error::CircuitOpen),
+            Some(_) => {
+                // Half-open: let this request through and hold back the others until it's done.
+                state.opened_at = Some(Instant::now());
+                drop(state);
+                Ok(())
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Records the outcome of a request that [`CircuitBreaker::check`] let through.
+    pub fn record(&self, result: &Result<Response>) {
+        // Nothing was sent, so this says nothing about the API.
+        if result.as_ref().is_err_and(|error| !reached_api(error)) {
+            return;
+        }
+
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        if !is_outage(result) {
+            *state = State::default();
+            return;
+        }
+
+        state.failures = state.failures.saturating_add(1);
+        if state.failures >= self.threshold {
+            state.opened_at = Some(Instant::now());
+        }
+    }
+}
+
+/// Returns whether `result` points to the API (or the network to it) being down, as opposed to a
+/// problem with the request itself.
+fn is_outage(result: &Result<Response>) -> bool {
+    result.as_ref().err().is_some_and(is_outage_error)
+}
+
+/// Returns whether the request failing with `error` was sent, successfully or not.
+fn reached_api(error: &Error) -> bool {
+    match error {
+        Error::Validation(_) | Error::Decode { .. } | Error::CircuitOpen => false,
+        Error::Http(error) => !error.is_builder(),
+        Error::Resend(_) | Error::Parse(_) | Error::UnexpectedContentType { .. } => true,
+        Error::Batch(error) => reached_api(error),
+    }
+}
+
+fn is_outage_error(error: &Error) -> bool {
+    match error {
+        Error::Validation(_) | Error::Decode { .. } | Error::CircuitOpen => false,
+        Error::Http(error) => {
+            error.is_timeout()
+                || error.is_connect()
+                || error
+                    .status()
+                    .is_some_and(|status| status.is_server_error())
+        }
+        Error::Resend(error) => error.status_code >= 500,
+        Error::Parse(_) | Error::UnexpectedContentType { .. } => true,
+        Error::Batch(error) => is_outage_error(error),
+    }
+}
+
+#[cfg(all(test, not(feature = "blocking")))]
+mod test {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    use crate::tests::mock::{MockResponse, MockServer};
+    use crate::types::CreateEmailBaseOptions;
+    use crate::{Error, Resend, Result};
+
+    #[tokio::test]
+    async fn trips_and_recovers() -> Result<()> {
+        let requests = AtomicUsize::new(0);
+        let server = MockServer::start(move |_| {
+            if requests.fetch_add(1, Ordering::SeqCst) < 2 {
+                let body = r#"{"statusCode":503,"message":"","name":"internal_server_error"}"#;
+                return MockResponse::json(503, body);
+            }
+            MockResponse::json(200, r#"{"id":"1"}"#)
+        });
+        let resend = Resend::builder("re_123")
+            .base_url(server.url())
+            .circuit_breaker(2, Duration::from_millis(200))
+            .build()?;
+        let email = CreateEmailBaseOptions::new("from@a.dev", ["to@a.dev"], "Subject");
+
+        for _ in 0..2 {
+            let result = resend.emails.send(email.clone()).await;
+            assert!(matches!(result, Err(Error::Resend(_))));
+        }
+
+        // Open, the request isn't sent.
+        let result = resend.emails.send(email.clone()).await;
+        assert!(matches!(result, Err(Error::CircuitOpen)));
+        assert_eq!(server.requests().len(), 2);
+
+        std::thread::sleep(Duration::from_millis(250));
+
+        // Half-open, the trial request succeeds and closes the breaker.
+        let _ = resend.emails.send(email.clone()).await?;
+        let _ = resend.emails.send(email).await?;
+        assert_eq!(server.requests().len(), 4);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn unsent_trial_keeps_breaker_open() -> Result<()> {
+        let server = MockServer::start(|_| {
+            let body = r#"{"statusCode":503,"message":"","name":"internal_server_error"}"#;
+            MockResponse::json(503, body)
+        });
+        let resend = Resend::builder("re_123")
+            .base_url(server.url())
+            .circuit_breaker(1, Duration::from_millis(100))
+            .max_body_size(1024)
+            .build()?;
+        let email = CreateEmailBaseOptions::new("from@a.dev", ["to@a.dev"], "Subject");
+
+        let result = resend.emails.send(email.clone()).await;
+        assert!(matches!(result, Err(Error::Resend(_))));
+
+        std::thread::sleep(Duration::from_millis(150));
+
+        // The trial request is too large to be sent, which doesn't close the breaker.
+        let large = email.clone().with_text(&"a".repeat(2048));
+        let result = resend.emails.send(large).await;
+        assert!(matches!(result, Err(Error::Validation(_))));
+
+        let result = resend.emails.send(email).await;
+        assert!(matches!(result, Err(Error::CircuitOpen)));
+        assert_eq!(server.requests().len(), 1);
+
+        Ok(())
+    }
+}
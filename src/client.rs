@@ -1,15 +1,23 @@
+use std::ffi::OsString;
 use std::sync::Arc;
-use std::{env, fmt};
+use std::{env, fmt, fs, io};
 
 #[cfg(feature = "blocking")]
-use reqwest::blocking::Client as ReqwestClient;
+use reqwest::blocking::{Client as ReqwestClient, ClientBuilder as ReqwestClientBuilder};
 #[cfg(not(feature = "blocking"))]
-use reqwest::Client as ReqwestClient;
+use reqwest::{Client as ReqwestClient, ClientBuilder as ReqwestClientBuilder};
 
-use crate::services::{ApiKeysSvc, AudiencesSvc, ContactsSvc, DomainsSvc, EmailsSvc};
-use crate::{batch::BatchSvc, config::Config};
+use crate::config::{client_builder, Config, SecretString};
+use crate::services::{ApiKeysSvc, AudiencesSvc, ContactsSvc, DomainsSvc, EmailsSvc, SendEmails};
+use crate::types::{
+    CreateEmailBaseOptions, EmailId, RateLimitHandle, RateLimitInfo, ResponseMetadata,
+};
+use crate::{batch::BatchSvc, ResendBuilder, Result};
 
 /// The [Resend](https://resend.com) client.
+///
+/// Cloning is cheap, clones share the same configuration and rate limiter so they can be handed
+/// out to tasks without exceeding the quota together.
 #[must_use]
 #[derive(Clone)]
 pub struct Resend {
@@ -33,14 +41,24 @@ impl Resend {
     /// ### Panics
     ///
     /// - Panics if the environment variable `RESEND_BASE_URL` is set but is not a valid `URL`.
+    /// - Panics if the environment variable `RESEND_TIMEOUT_MS` is set but is not a valid amount
+    ///   of milliseconds.
     ///
     /// [`Resend`]: https://resend.com
     pub fn new(api_key: &str) -> Self {
-        Self::with_client(api_key, ReqwestClient::default())
+        let client = client_builder()
+            .expect("env variable `RESEND_TIMEOUT_MS` should be a valid u64")
+            .build()
+            .expect("the default client should be valid");
+
+        Self::with_client(api_key, client)
     }
 
     /// Creates a new [`Resend`] client with a provided [`reqwest::Client`].
     ///
+    /// Requests are still rate limited like with [`Resend::new`], use
+    /// [`ResendBuilder::rate_limit`] to opt out.
+    ///
     /// ### Panics
     ///
     /// - Panics if the environment variable `RESEND_BASE_URL` is set but is not a valid `URL`.
@@ -48,7 +66,39 @@ impl Resend {
     /// [`Resend`]: https://resend.com
     /// [`reqwest::Client`]: ReqwestClient
     pub fn with_client(api_key: &str, client: ReqwestClient) -> Self {
-        let inner = Arc::new(Config::new(api_key, client));
+        Self::from_config(Config::new(SecretString::from(api_key), client))
+    }
+
+    /// Creates a new [`Resend`] client, configuring the underlying [`reqwest::Client`] through
+    /// `configure` (proxies, TLS, timeouts, default headers, ...).
+    ///
+    /// Fails if the configured [`reqwest::Client`] can't be built, or if the environment variable
+    /// `RESEND_TIMEOUT_MS` is set but is not a valid amount of milliseconds.
+    ///
+    /// ### Panics
+    ///
+    /// - Panics if the environment variable `RESEND_BASE_URL` is set but is not a valid `URL`.
+    ///
+    /// [`reqwest::Client`]: ReqwestClient
+    pub fn with_reqwest_builder(
+        api_key: &str,
+        configure: impl FnOnce(ReqwestClientBuilder) -> ReqwestClientBuilder,
+    ) -> Result<Self> {
+        let client = configure(client_builder()?).build()?;
+        Ok(Self::with_client(api_key, client))
+    }
+
+    /// Creates a [`ResendBuilder`] to configure the underlying [`reqwest::Client`].
+    ///
+    /// [`reqwest::Client`]: ReqwestClient
+    #[inline]
+    pub fn builder(api_key: &str) -> ResendBuilder {
+        ResendBuilder::new(api_key)
+    }
+
+    /// Creates a new [`Resend`] client sharing the provided [`Config`] across all services.
+    pub(crate) fn from_config(config: Config) -> Self {
+        let inner = Arc::new(config);
 
         Self {
             api_keys: ApiKeysSvc(inner.clone()),
@@ -67,11 +117,14 @@ impl Resend {
         self.config().user_agent.as_str()
     }
 
-    /// Returns the reference to the provided `API key`.
+    /// Returns the reference to the provided `API key`, see [`SecretString::expose_secret`] to
+    /// read it in clear text.
+    ///
+    /// The `Debug` output of the client never includes it.
     #[inline]
     #[must_use]
-    pub fn api_key(&self) -> &str {
-        self.config().api_key.as_ref()
+    pub fn api_key(&self) -> &SecretString {
+        &self.config().api_key
     }
 
     /// Returns the reference to the used `base URL`.
@@ -94,6 +147,83 @@ impl Resend {
         self.config().client.clone()
     }
 
+    /// Returns the rate limit state reported by the most recent response that included the
+    /// `ratelimit-*` headers, successful or not.
+    ///
+    /// Useful to slow down proactively before the API starts responding with 429s.
+    #[inline]
+    #[must_use]
+    pub fn last_rate_limit_info(&self) -> Option<RateLimitInfo> {
+        self.config().last_rate_limit_info()
+    }
+
+    /// Sends a plain text email in one call, see [`EmailsSvc::send`].
+    ///
+    /// Returns the id of the sent email.
+    #[maybe_async::maybe_async]
+    pub async fn send_text<T, A>(
+        &self,
+        from: &str,
+        to: T,
+        subject: &str,
+        text: &str,
+    ) -> Result<EmailId>
+    where
+        T: IntoIterator<Item = A> + Send,
+        A: Into<String>,
+    {
+        let email = CreateEmailBaseOptions::new(from, to, subject).with_text(text);
+        Ok(self.emails.send(email).await?.id)
+    }
+
+    /// Sends an HTML email in one call, see [`EmailsSvc::send`].
+    ///
+    /// Returns the id of the sent email.
+    #[maybe_async::maybe_async]
+    pub async fn send_html<T, A>(
+        &self,
+        from: &str,
+        to: T,
+        subject: &str,
+        html: &str,
+    ) -> Result<EmailId>
+    where
+        T: IntoIterator<Item = A> + Send,
+        A: Into<String>,
+    {
+        let email = CreateEmailBaseOptions::new(from, to, subject).with_html(html);
+        Ok(self.emails.send(email).await?.id)
+    }
+
+    /// Returns the `/emails` service type-erased behind [`SendEmails`], to store it where the
+    /// concrete type shouldn't leak, e.g. as a field next to a [`MockClient`] in tests.
+    ///
+    /// [`MockClient`]: crate::test_util::MockClient
+    #[inline]
+    #[must_use]
+    pub fn emails_boxed(&self) -> Box<dyn SendEmails> {
+        Box::new(self.emails.clone())
+    }
+
+    /// Returns a handle to the rate limiter shared by all services of this client.
+    ///
+    /// Acquire it before making your own requests to the `Resend` API so that they count
+    /// towards the same quota.
+    #[inline]
+    #[must_use]
+    pub fn rate_limiter(&self) -> RateLimitHandle {
+        self.config().limiter.clone()
+    }
+
+    /// Returns the [`ResponseMetadata`] of the most recent response, successful or not.
+    ///
+    /// Its [`request_id`](ResponseMetadata::request_id) is what Resend support asks for.
+    #[inline]
+    #[must_use]
+    pub fn last_response_metadata(&self) -> Option<ResponseMetadata> {
+        self.config().last_response_metadata()
+    }
+
     /// Returns the reference to the inner [`Config`].
     #[inline]
     fn config(&self) -> &Config {
@@ -102,18 +232,45 @@ impl Resend {
 }
 
 impl Default for Resend {
-    /// Creates a new [`Resend`] client from the `RESEND_API_KEY` environment variable .
+    /// Creates a new [`Resend`] client from the `RESEND_API_KEY` environment variable, or from the
+    /// file at `RESEND_API_KEY_FILE` if `RESEND_API_KEY` is not set.
     ///
     /// ### Panics
     ///
-    /// - Panics if the environment variable `RESEND_API_KEY` is not set.
+    /// - Panics if neither `RESEND_API_KEY` nor `RESEND_API_KEY_FILE` is set.
+    /// - Panics if `RESEND_API_KEY_FILE` is used but the file can't be read.
     /// - Panics if the environment variable `RESEND_BASE_URL` is set but is not a valid `URL`.
+    /// - Panics if the environment variable `RESEND_TIMEOUT_MS` is set but is not a valid amount
+    ///   of milliseconds.
     fn default() -> Self {
-        let api_key = env::var("RESEND_API_KEY")
-            .expect("env variable `RESEND_API_KEY` should be a valid API key");
+        let api_key = api_key_from(
+            env::var("RESEND_API_KEY").ok(),
+            env::var_os("RESEND_API_KEY_FILE"),
+        )
+        .expect("file at `RESEND_API_KEY_FILE` should be readable")
+        .expect("env variable `RESEND_API_KEY` or `RESEND_API_KEY_FILE` should be set");
+
+        let client = client_builder()
+            .expect("env variable `RESEND_TIMEOUT_MS` should be a valid u64")
+            .build()
+            .expect("the default client should be valid");
+
+        Self::from_config(Config::new(SecretString::from(api_key), client))
+    }
+}
 
-        Self::new(api_key.as_str())
+/// Returns `api_key`, or else the trimmed content of the file at `api_key_file`.
+fn api_key_from(
+    api_key: Option<String>,
+    api_key_file: Option<OsString>,
+) -> io::Result<Option<String>> {
+    if api_key.is_some() {
+        return Ok(api_key);
     }
+
+    api_key_file
+        .map(|path| fs::read_to_string(path).map(|key| key.trim().to_owned()))
+        .transpose()
 }
 
 impl fmt::Debug for Resend {
@@ -121,3 +278,102 @@ impl fmt::Debug for Resend {
         fmt::Debug::fmt(&self.emails, f)
     }
 }
+
+#[cfg(test)]
+mod test {
+    #[cfg(not(feature = "blocking"))]
+    use reqwest::header::{HeaderMap, HeaderValue};
+
+    use super::api_key_from;
+    #[cfg(not(feature = "blocking"))]
+    use crate::tests::mock::{MockResponse, MockServer};
+    #[cfg(not(feature = "blocking"))]
+    use crate::types::CreateEmailBaseOptions;
+    #[cfg(not(feature = "blocking"))]
+    use crate::{Resend, Result};
+
+    #[tokio::test]
+    #[cfg(not(feature = "blocking"))]
+    async fn with_reqwest_builder() -> Result<()> {
+        let server = MockServer::start(|_| MockResponse::json(200, r#"{"id":"1"}"#));
+
+        let mut headers = HeaderMap::new();
+        let _ = headers.insert("x-tenant", HeaderValue::from_static("acme"));
+        let resend =
+            Resend::with_reqwest_builder("re_123", |builder| builder.default_headers(headers))?;
+
+        let email = CreateEmailBaseOptions::new("from@a.dev", ["to@a.dev"], "Subject");
+        let emails = resend.emails.with_base_url(server.url());
+        let _ = emails.send(email).await?;
+
+        let requests = server.requests();
+        assert_eq!(requests[0].header("x-tenant"), Some("acme"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[cfg(not(feature = "blocking"))]
+    async fn emails_boxed() -> Result<()> {
+        use crate::services::SendEmails;
+
+        struct App {
+            emails: Box<dyn SendEmails>,
+        }
+
+        let server = MockServer::start(|_| MockResponse::json(200, r#"{"id":"1"}"#));
+        let resend = Resend::builder("re_123").base_url(server.url()).build()?;
+        let app = App {
+            emails: resend.emails_boxed(),
+        };
+
+        let email = CreateEmailBaseOptions::new("from@a.dev", ["to@a.dev"], "Subject");
+        let id = app.emails.send(email).await?.id;
+        assert_eq!(id.as_ref(), "1");
+        assert_eq!(server.requests().len(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn api_key_from_file() -> std::io::Result<()> {
+        let path = std::env::temp_dir().join(format!("resend-api-key-{}", std::process::id()));
+        std::fs::write(&path, "re_from_file\n")?;
+
+        let from_file = api_key_from(None, Some(path.clone().into_os_string()))?;
+        assert_eq!(from_file.as_deref(), Some("re_from_file"));
+
+        // `RESEND_API_KEY` takes precedence.
+        let from_env = api_key_from(Some("re_from_env".to_owned()), Some(path.clone().into()))?;
+        assert_eq!(from_env.as_deref(), Some("re_from_env"));
+
+        assert!(api_key_from(None, None)?.is_none());
+
+        std::fs::remove_file(path)
+    }
+
+    #[tokio::test]
+    #[cfg(not(feature = "blocking"))]
+    async fn send_text_and_html() -> Result<()> {
+        let server = MockServer::start(|_| MockResponse::json(200, r#"{"id":"1"}"#));
+        let resend = Resend::builder("re_123").base_url(server.url()).build()?;
+
+        let id = resend
+            .send_text("from@a.dev", ["to@a.dev"], "Subject", "Hello World!")
+            .await?;
+        assert_eq!(id.as_ref(), "1");
+
+        let id = resend
+            .send_html("from@a.dev", ["to@a.dev"], "Subject", "<p>Hello World!</p>")
+            .await?;
+        assert_eq!(id.as_ref(), "1");
+
+        let requests = server.requests();
+        assert!(requests[0].body_str().contains(r#""text":"Hello World!""#));
+        assert!(requests[1]
+            .body_str()
+            .contains(r#""html":"<p>Hello World!</p>""#));
+
+        Ok(())
+    }
+}
@@ -1,34 +1,53 @@
-#[cfg(not(feature = "blocking"))]
-use governor::{
-    clock::{QuantaClock, QuantaInstant},
-    middleware::NoOpMiddleware,
-    state::{InMemoryState, NotKeyed},
-    Quota, RateLimiter,
-};
+use governor::Quota;
 #[cfg(feature = "blocking")]
-use reqwest::blocking::{Client, RequestBuilder, Response};
-use reqwest::header::USER_AGENT;
+use reqwest::blocking::{Client, ClientBuilder, RequestBuilder, Response};
+use reqwest::header::{
+    HeaderMap, HeaderName, HeaderValue, AUTHORIZATION, CONTENT_TYPE, USER_AGENT,
+};
 #[cfg(not(feature = "blocking"))]
-use reqwest::{Client, RequestBuilder, Response};
+use reqwest::{Client, ClientBuilder, RequestBuilder, Response};
 use reqwest::{Method, Url};
-use std::{env, fmt};
-#[cfg(not(feature = "blocking"))]
-use std::{num::NonZeroU32, sync::Arc, time::Duration};
+use serde::de::DeserializeOwned;
+use std::sync::{Arc, PoisonError, RwLock};
+use std::{borrow::Cow, env, fmt};
+use std::{num::NonZeroU32, time::Duration};
 
-use crate::{error::types::ErrorResponse, Error, Result};
+#[cfg(feature = "circuit-breaker")]
+use crate::circuit_breaker::CircuitBreaker;
+use crate::limits::MAX_ATTACHMENT_BYTES;
+use crate::types::{
+    ErrorResponse, RateLimitHandle, RateLimitInfo, ResponseMetadata, Tag, ValidationError,
+};
+use crate::{Error, Result};
+
+/// Default [`Config::max_body_size`]: the most attachments the API accepts per email, base64
+/// encoded, plus 2 MiB for the rest of the email.
+const DEFAULT_MAX_BODY_SIZE: usize = MAX_ATTACHMENT_BYTES.div_ceil(3) * 4 + 2 * 1024 * 1024;
 
+#[derive(Clone)]
 pub struct Config {
     pub(crate) user_agent: String,
-    pub(crate) api_key: String,
+    pub(crate) api_key: Arc<SecretString>,
     pub(crate) base_url: Url,
     pub(crate) client: Client,
-    #[cfg(not(feature = "blocking"))]
-    limiter: Arc<RateLimiter<NotKeyed, InMemoryState, QuantaClock, NoOpMiddleware<QuantaInstant>>>,
+    pub(crate) max_body_size: usize,
+    pub(crate) default_tags: Vec<Tag>,
+    pub(crate) default_headers: HeaderMap,
+    pub(crate) auto_idempotency: bool,
+    #[cfg(feature = "tracing")]
+    pub(crate) warn_on_missing_text: bool,
+    pub(crate) auth_header: Option<AuthHeader>,
+    #[cfg(feature = "circuit-breaker")]
+    pub(crate) circuit_breaker: Option<Arc<CircuitBreaker>>,
+    pub(crate) limiter: RateLimitHandle,
+    pub(crate) priority_limiter: RateLimitHandle,
+    last_rate_limit_info: Arc<RwLock<Option<RateLimitInfo>>>,
+    last_response_metadata: Arc<RwLock<Option<ResponseMetadata>>>,
 }
 
 impl Config {
     /// Creates a new [`Config`].
-    pub fn new(api_key: &str, client: Client) -> Self {
+    pub fn new(api_key: SecretString, client: Client) -> Self {
         let env_base_url = env::var("RESEND_BASE_URL")
             .map_or_else(
                 |_| Url::parse("https://api.resend.com"),
@@ -38,31 +57,36 @@ impl Config {
 
         let env_user_agent = format!("{}/{}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"));
 
-        // ==== Rate limiting is a non-blocking thing only ====
-        #[cfg(not(feature = "blocking"))]
-        let rate_limit_per_sec = env::var("RESEND_RATE_LIMIT")
+        let rate_limit = env::var("RESEND_RATE_LIMIT")
             .unwrap_or_else(|_| "9".to_owned())
             .parse::<u32>()
             .expect("env variable `RESEND_RATE_LIMIT` should be a valid u32");
 
-        #[cfg(not(feature = "blocking"))]
-        let quota = Quota::with_period(Duration::from_millis(1100))
-            .expect("Valid quota")
-            .allow_burst(
-                NonZeroU32::new(rate_limit_per_sec).expect("Rate limit is a valid non zero u32"),
-            );
+        let rate_limit = NonZeroU32::new(rate_limit).expect("Rate limit is a valid non zero u32");
+        let period = env::var("RESEND_RATE_LIMIT_PERIOD").ok();
+        let (quota, priority_quota) = lane_quotas(rate_limit, period.as_deref());
 
-        #[cfg(not(feature = "blocking"))]
-        let limiter = Arc::new(RateLimiter::direct(quota));
-        // ====================================================
+        let limiter = RateLimitHandle::new(quota);
+        let priority_limiter = priority_quota.map_or_else(|| limiter.clone(), RateLimitHandle::new);
 
         Self {
             user_agent: env_user_agent,
-            api_key: api_key.to_owned(),
+            api_key: Arc::new(api_key),
             base_url: env_base_url,
             client,
-            #[cfg(not(feature = "blocking"))]
+            max_body_size: DEFAULT_MAX_BODY_SIZE,
+            default_tags: Vec::new(),
+            default_headers: HeaderMap::new(),
+            auto_idempotency: false,
+            #[cfg(feature = "tracing")]
+            warn_on_missing_text: false,
+            auth_header: None,
+            #[cfg(feature = "circuit-breaker")]
+            circuit_breaker: None,
             limiter,
+            priority_limiter,
+            last_rate_limit_info: Arc::default(),
+            last_response_metadata: Arc::default(),
         }
     }
 
@@ -73,25 +97,141 @@ impl Config {
             .join(path)
             .expect("should be a valid API endpoint");
 
-        self.client
-            .request(method, path)
-            .bearer_auth(self.api_key.as_str())
-            .header(USER_AGENT, self.user_agent.as_str())
+        let mut request = self.client.request(method, path);
+        if !self.default_headers.is_empty() {
+            // The crate's own headers take precedence.
+            let mut headers = self.default_headers.clone();
+            let _ = headers.remove(USER_AGENT);
+            let _ = headers.remove(AUTHORIZATION);
+            if let Some(auth) = &self.auth_header {
+                let _ = headers.remove(&auth.name);
+            }
+            request = request.headers(headers);
+        }
+        let request = request.header(USER_AGENT, self.user_agent.as_str());
+
+        let Some(auth) = &self.auth_header else {
+            return request.bearer_auth(self.api_key.expose_secret());
+        };
+
+        let value = (auth.format)(self.api_key.expose_secret());
+        match HeaderValue::from_str(&value) {
+            Ok(mut value) => {
+                value.set_sensitive(true);
+                request.header(&auth.name, value)
+            }
+            // Surfaces the invalid value as an error once the request is built.
+            Err(_) => request.header(&auth.name, value),
+        }
+    }
+
+    /// Returns the [`RateLimitInfo`] of the most recent response that carried one.
+    pub fn last_rate_limit_info(&self) -> Option<RateLimitInfo> {
+        *self
+            .last_rate_limit_info
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Returns the [`ResponseMetadata`] of the most recent response.
+    pub fn last_response_metadata(&self) -> Option<ResponseMetadata> {
+        self.last_response_metadata
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
     }
 
     #[maybe_async::maybe_async]
     pub async fn send(&self, request: RequestBuilder) -> Result<Response> {
-        #[cfg(not(feature = "blocking"))]
-        {
-            let jitter =
-                governor::Jitter::new(Duration::from_millis(10), Duration::from_millis(50));
-            self.limiter.until_ready_with_jitter(jitter).await;
+        self.send_with(&self.limiter, request).await
+    }
+
+    /// Same as [`Config::send`] but goes through the priority share of the quota.
+    #[maybe_async::maybe_async]
+    pub async fn send_priority(&self, request: RequestBuilder) -> Result<Response> {
+        self.send_with(&self.priority_limiter, request).await
+    }
+
+    #[maybe_async::maybe_async]
+    async fn send_with(
+        &self,
+        limiter: &RateLimitHandle,
+        request: RequestBuilder,
+    ) -> Result<Response> {
+        #[cfg(feature = "circuit-breaker")]
+        if let Some(breaker) = &self.circuit_breaker {
+            breaker.check()?;
         }
 
+        let result = self.execute(limiter, request).await;
+
+        #[cfg(feature = "circuit-breaker")]
+        if let Some(breaker) = &self.circuit_breaker {
+            breaker.record(&result);
+        }
+
+        result
+    }
+
+    #[maybe_async::maybe_async]
+    async fn execute(
+        &self,
+        limiter: &RateLimitHandle,
+        request: RequestBuilder,
+    ) -> Result<Response> {
         let request = request.build()?;
 
+        // Fail before waiting on the rate limiter or uploading the body.
+        let size = request
+            .body()
+            .and_then(|body| body.as_bytes())
+            .map_or(0, <[u8]>::len);
+        if size > self.max_body_size {
+            let max = self.max_body_size;
+            return Err(ValidationError::BodyTooLarge { size, max }.into());
+        }
+
+        #[cfg(feature = "tracing")]
+        let span = tracing::debug_span!(
+            "resend_request",
+            method = %request.method(),
+            path = request.url().path(),
+            rate_limit_wait_ms = tracing::field::Empty,
+        );
+        #[cfg(feature = "tracing")]
+        let waiting = std::time::Instant::now();
+
+        limiter.acquire().await;
+
+        // Recorded separately so that time spent queued isn't mistaken for network latency.
+        #[cfg(feature = "tracing")]
+        let _ = span.record(
+            "rate_limit_wait_ms",
+            u64::try_from(waiting.elapsed().as_millis()).unwrap_or(u64::MAX),
+        );
+
+        #[cfg(all(feature = "tracing", not(feature = "blocking")))]
+        let response = tracing::Instrument::instrument(self.client.execute(request), span).await?;
+        #[cfg(all(feature = "tracing", feature = "blocking"))]
+        let response = span.in_scope(|| self.client.execute(request))?;
+        #[cfg(not(feature = "tracing"))]
         let response = self.client.execute(request).await?;
 
+        let metadata = ResponseMetadata::from_headers(response.headers(), size);
+        if let Some(info) = metadata.rate_limit {
+            let mut last = self
+                .last_rate_limit_info
+                .write()
+                .unwrap_or_else(PoisonError::into_inner);
+            *last = Some(info);
+        }
+        let request_id = metadata.request_id.clone();
+        let rate_limit = metadata.rate_limit;
+        *self
+            .last_response_metadata
+            .write()
+            .unwrap_or_else(PoisonError::into_inner) = Some(metadata);
+
         match response.status() {
             x if x.is_client_error() || x.is_server_error() => {
                 // TODO: Make this more testable
@@ -105,21 +245,289 @@ impl Config {
                     return Err(Error::Parse(response.text().await?));
                 }
 
-                let error = response.json::<ErrorResponse>().await?;
+                let mut error = decode::<ErrorResponse>(response).await?;
+                error.request_id = request_id;
+                error.rate_limit = rate_limit.map(Box::new);
                 Err(Error::Resend(error))
             }
-            _ => Ok(response),
+            _ => {
+                let content_type = response
+                    .headers()
+                    .get(CONTENT_TYPE)
+                    .and_then(|value| value.to_str().ok())
+                    .unwrap_or_default();
+
+                // Responses without a body (or a content type) are left to the caller.
+                if content_type.is_empty() || content_type.contains("json") {
+                    return Ok(response);
+                }
+
+                let content_type = content_type.to_owned();
+                let body_snippet = response.text().await?.chars().take(200).collect();
+                Err(Error::UnexpectedContentType {
+                    content_type,
+                    body_snippet,
+                })
+            }
         }
     }
 }
 
+/// Header carrying the API key instead of `Authorization: Bearer`.
+#[derive(Clone)]
+pub struct AuthHeader {
+    pub(crate) name: HeaderName,
+    pub(crate) format: Arc<dyn Fn(&str) -> String + Send + Sync>,
+}
+
+/// Secret, such as the API key of a [`Resend`] client, only shown through
+/// [`SecretString::expose_secret`].
+///
+/// It isn't `Clone` to not leave copies of it in memory, and with the `zeroize` feature, it is
+/// overwritten in memory when dropped.
+///
+/// [`Resend`]: crate::Resend
+pub struct SecretString(String);
+
+impl SecretString {
+    /// Returns the secret in clear text.
+    #[must_use]
+    pub const fn expose_secret(&self) -> &str {
+        self.0.as_str()
+    }
+}
+
+impl From<String> for SecretString {
+    fn from(secret: String) -> Self {
+        Self(secret)
+    }
+}
+
+impl From<&str> for SecretString {
+    fn from(secret: &str) -> Self {
+        Self(secret.to_owned())
+    }
+}
+
+impl fmt::Debug for SecretString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("re_*********")
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Drop for SecretString {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(&mut self.0);
+    }
+}
+
+/// Builds an API endpoint path by appending `/`-separated `segments` to a static `base`.
+///
+/// Only allocates (once, with the exact capacity) when there are segments to append.
+pub fn path<'a>(base: &'a str, segments: &[&str]) -> Cow<'a, str> {
+    if segments.is_empty() {
+        return Cow::Borrowed(base);
+    }
+
+    let len = base.len() + segments.iter().map(|s| s.len() + 1).sum::<usize>();
+    let mut path = String::with_capacity(len);
+    path.push_str(base);
+
+    for segment in segments {
+        path.push('/');
+        path.push_str(segment);
+    }
+
+    Cow::Owned(path)
+}
+
+/// Creates a [`ClientBuilder`] with the timeout set by the `RESEND_TIMEOUT_MS` environment
+/// variable, if any.
+///
+/// Fails if `RESEND_TIMEOUT_MS` is set but is not a valid amount of milliseconds.
+pub fn client_builder() -> Result<ClientBuilder, ValidationError> {
+    let builder = Client::builder();
+
+    Ok(match env_timeout() {
+        Some(timeout_ms) => builder.timeout(timeout_from(&timeout_ms)?),
+        None => builder,
+    })
+}
+
+/// Returns the value of the `RESEND_TIMEOUT_MS` environment variable, if set.
+pub fn env_timeout() -> Option<String> {
+    env::var("RESEND_TIMEOUT_MS").ok()
+}
+
+/// Parses the value of the `RESEND_TIMEOUT_MS` environment variable.
+pub fn timeout_from(timeout_ms: &str) -> Result<Duration, ValidationError> {
+    timeout_ms
+        .parse::<u64>()
+        .ok()
+        .map(Duration::from_millis)
+        .ok_or_else(|| ValidationError::InvalidEnvVar {
+            name: "RESEND_TIMEOUT_MS",
+            value: timeout_ms.to_owned(),
+        })
+}
+
+/// Creates the [`Quota`] allowing `rate_limit` requests per `period`, the value of the
+/// `RESEND_RATE_LIMIT_PERIOD` environment variable (`second` by default, or `minute`).
+///
+/// ### Panics
+///
+/// - Panics if `period` is neither `second` nor `minute`.
+fn quota_from(rate_limit: NonZeroU32, period: Option<&str>) -> Quota {
+    match period.unwrap_or("second") {
+        // The window is slightly longer than a second to avoid failures.
+        "second" => Quota::with_period(Duration::from_millis(1100))
+            .expect("Valid quota")
+            .allow_burst(rate_limit),
+        "minute" => Quota::per_minute(rate_limit),
+        _ => panic!("env variable `RESEND_RATE_LIMIT_PERIOD` should be `second` or `minute`"),
+    }
+}
+
+/// Splits `rate_limit` into the quotas of the regular and the priority lane, the priority lane
+/// getting one request per period so that both lanes together stay within `rate_limit`.
+///
+/// Returns no priority quota if `rate_limit` is 1, both lanes then share the regular quota.
+fn lane_quotas(rate_limit: NonZeroU32, period: Option<&str>) -> (Quota, Option<Quota>) {
+    NonZeroU32::new(rate_limit.get() - 1).map_or_else(
+        || (quota_from(rate_limit, period), None),
+        |regular| {
+            let priority = quota_from(NonZeroU32::MIN, period);
+            (quota_from(regular, period), Some(priority))
+        },
+    )
+}
+
+/// Decodes the JSON body of `response`, keeping the body in [`Error::Decode`] if it doesn't
+/// match `T`.
+#[maybe_async::maybe_async]
+pub async fn decode<T: DeserializeOwned>(response: Response) -> Result<T> {
+    let body = response.text().await?;
+    serde_json::from_str(&body).map_err(|error| Error::Decode { error, body })
+}
+
+/// Waits for `duration` without blocking the executor.
+#[cfg(not(feature = "blocking"))]
+pub async fn sleep(duration: Duration) {
+    futures_timer::Delay::new(duration).await;
+}
+
+/// Blocks the current thread for `duration`.
+#[cfg(feature = "blocking")]
+pub fn sleep(duration: Duration) {
+    std::thread::sleep(duration);
+}
+
 impl fmt::Debug for Config {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         // Don't output API key.
         f.debug_struct("Client")
-            .field("api_key", &"re_*********")
+            .field("api_key", &self.api_key)
             .field("user_agent", &self.user_agent.as_str())
             .field("base_url", &self.base_url.as_str())
             .finish_non_exhaustive()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::borrow::Cow;
+    use std::num::NonZeroU32;
+    use std::time::Duration;
+
+    use super::{lane_quotas, path, quota_from, timeout_from, SecretString};
+    use crate::types::ValidationError;
+
+    #[test]
+    fn api_key_redacted() {
+        let resend = crate::Resend::new("re_secret_123");
+
+        assert!(!format!("{resend:?}").contains("re_secret_123"));
+        assert!(!format!("{:?}", SecretString::from("re_secret_123")).contains("secret"));
+        assert_eq!(resend.api_key().expose_secret(), "re_secret_123");
+    }
+
+    #[test]
+    fn timeout_from_env() {
+        assert_eq!(timeout_from("2500"), Ok(Duration::from_millis(2500)));
+        assert_eq!(
+            timeout_from("5s"),
+            Err(ValidationError::InvalidEnvVar {
+                name: "RESEND_TIMEOUT_MS",
+                value: "5s".to_owned(),
+            })
+        );
+    }
+
+    #[test]
+    fn quota_per_minute() {
+        let rate_limit = NonZeroU32::new(100).expect("non zero");
+
+        let quota = quota_from(rate_limit, Some("minute"));
+        assert_eq!(quota.burst_size(), rate_limit);
+        assert_eq!(quota.burst_size_replenished_in().as_secs(), 60);
+
+        let quota = quota_from(rate_limit, None);
+        assert_eq!(quota.replenish_interval(), Duration::from_millis(1100));
+    }
+
+    #[test]
+    fn priority_share_of_quota() {
+        let rate_limit = NonZeroU32::new(9).expect("non zero");
+
+        let (quota, priority_quota) = lane_quotas(rate_limit, None);
+        let priority_quota = priority_quota.expect("room for a priority lane");
+        assert_eq!(
+            quota.burst_size().get() + priority_quota.burst_size().get(),
+            9
+        );
+        assert_eq!(priority_quota.burst_size(), NonZeroU32::MIN);
+        assert_eq!(
+            priority_quota.replenish_interval(),
+            Duration::from_millis(1100)
+        );
+
+        let (quota, priority_quota) = lane_quotas(rate_limit, Some("minute"));
+        assert_eq!(quota.burst_size_replenished_in().as_secs(), 60);
+        assert_eq!(
+            priority_quota.map(|quota| quota.burst_size_replenished_in().as_secs()),
+            Some(60)
+        );
+
+        // Nothing left to carve out.
+        let (quota, priority_quota) = lane_quotas(NonZeroU32::MIN, None);
+        assert_eq!(quota.burst_size(), NonZeroU32::MIN);
+        assert!(priority_quota.is_none());
+    }
+
+    #[test]
+    #[should_panic = "`RESEND_RATE_LIMIT_PERIOD` should be `second` or `minute`"]
+    fn invalid_quota_period() {
+        let _ = quota_from(NonZeroU32::MIN, Some("hour"));
+    }
+
+    #[test]
+    fn path_matches_format() {
+        let (audience_id, contact_id) = ("a1", "c1");
+
+        assert!(matches!(path("/emails", &[]), Cow::Borrowed("/emails")));
+        assert_eq!(
+            path("/audiences", &[audience_id]),
+            format!("/audiences/{audience_id}")
+        );
+        assert_eq!(
+            path("/audiences", &[audience_id, "contacts", contact_id]),
+            format!("/audiences/{audience_id}/contacts/{contact_id}")
+        );
+
+        // Allocated once, with the exact capacity.
+        let path = path("/domains", &["d1", "verify"]).into_owned();
+        assert_eq!(path, "/domains/d1/verify");
+        assert_eq!(path.len(), path.capacity());
+    }
+}
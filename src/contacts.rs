@@ -3,8 +3,13 @@ use std::sync::Arc;
 
 use reqwest::Method;
 
-use crate::types::{Contact, ContactChanges, ContactData, ContactId};
-use crate::{Config, Result};
+use crate::types::{
+    AudienceId, Contact, ContactChanges, ContactData, ContactId, ListContactsOptions, Page,
+};
+use crate::{
+    config::{decode, path},
+    Config, Error, Result,
+};
 
 use self::types::UpdateContactResponse;
 
@@ -22,30 +27,85 @@ impl ContactsSvc {
     // Reasoning for allow: https://github.com/resend/resend-rust/pull/1#issuecomment-2081646115
     #[allow(clippy::needless_pass_by_value)]
     pub async fn create(&self, audience_id: &str, contact: ContactData) -> Result<ContactId> {
-        let path = format!("/audiences/{audience_id}/contacts");
+        let path = path("/audiences", &[audience_id, "contacts"]);
 
         let request = self.0.build(Method::POST, &path);
         let response = self.0.send(request.json(&contact)).await?;
-        let content = response.json::<types::CreateContactResponse>().await?;
+        let content = decode::<types::CreateContactResponse>(response).await?;
 
         Ok(content.id)
     }
 
-    /// Retrieves a single contact from an audience.
+    /// Creates a contact inside an audience, or updates the existing contact with the same email
+    /// if creating it conflicts (`409`).
+    ///
+    /// Returns a contact id.
+    ///
+    /// <https://resend.com/docs/api-reference/contacts/create-contact>
+    #[maybe_async::maybe_async]
+    pub async fn upsert(&self, audience_id: &str, contact: ContactData) -> Result<ContactId> {
+        let path = path("/audiences", &[audience_id, "contacts"]);
+
+        let request = self.0.build(Method::POST, &path);
+        match self.0.send(request.json(&contact)).await {
+            Ok(response) => {
+                let content = decode::<types::CreateContactResponse>(response).await?;
+                Ok(content.id)
+            }
+            Err(Error::Resend(error)) if error.status_code == 409 => {
+                let changes = ContactChanges {
+                    first_name: contact.first_name,
+                    last_name: contact.last_name,
+                    unsubscribed: contact.unsubscribed,
+                    metadata: contact.metadata,
+                };
+                let audience_id = AudienceId::new(audience_id);
+                let contact_id = ContactId::new(&contact.email);
+                let updated = self.update(&audience_id, &contact_id, changes).await?;
+                Ok(updated.id)
+            }
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Retrieves a single contact from an audience by their ID or email.
+    ///
+    /// To look a contact up by email, wrap it with [`ContactId::new`].
     ///
     /// <https://resend.com/docs/api-reference/contacts/get-contact>
     #[maybe_async::maybe_async]
-    pub async fn get(&self, contact_id: &str, audience_id: &str) -> Result<Contact> {
-        let path = format!("/audiences/{audience_id}/contacts/{contact_id}");
+    pub async fn get(&self, audience_id: &AudienceId, contact_id: &ContactId) -> Result<Contact> {
+        let path = path(
+            "/audiences",
+            &[audience_id.as_ref(), "contacts", contact_id.as_ref()],
+        );
 
         let request = self.0.build(Method::GET, &path);
         let response = self.0.send(request).await?;
-        let content = response.json::<Contact>().await?;
+        let content = decode::<Contact>(response).await?;
 
         Ok(content)
     }
 
-    /// Updates an existing contact.
+    /// Same as [`ContactsSvc::get`] but returns `None` if the contact doesn't exist.
+    ///
+    /// <https://resend.com/docs/api-reference/contacts/get-contact>
+    #[maybe_async::maybe_async]
+    pub async fn try_get(
+        &self,
+        audience_id: &AudienceId,
+        contact_id: &ContactId,
+    ) -> Result<Option<Contact>> {
+        match self.get(audience_id, contact_id).await {
+            Ok(contact) => Ok(Some(contact)),
+            Err(e) if e.is_not_found() => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Updates an existing contact, identified by their ID or email.
+    ///
+    /// To update a contact by email, wrap it with [`ContactId::new`].
     ///
     /// <https://resend.com/docs/api-reference/contacts/update-contact>
     #[maybe_async::maybe_async]
@@ -53,15 +113,18 @@ impl ContactsSvc {
     #[allow(clippy::needless_pass_by_value)]
     pub async fn update(
         &self,
-        contact_id: &str,
-        audience_id: &str,
+        audience_id: &AudienceId,
+        contact_id: &ContactId,
         update: ContactChanges,
     ) -> Result<UpdateContactResponse> {
-        let path = format!("/audiences/{audience_id}/contacts/{contact_id}");
+        let path = path(
+            "/audiences",
+            &[audience_id.as_ref(), "contacts", contact_id.as_ref()],
+        );
 
         let request = self.0.build(Method::PATCH, &path);
         let response = self.0.send(request.json(&update)).await?;
-        let content = response.json::<UpdateContactResponse>().await?;
+        let content = decode::<UpdateContactResponse>(response).await?;
 
         Ok(content)
     }
@@ -73,11 +136,11 @@ impl ContactsSvc {
     /// <https://resend.com/docs/api-reference/contacts/delete-contact>
     #[maybe_async::maybe_async]
     pub async fn delete_by_email(&self, audience_id: &str, email: &str) -> Result<bool> {
-        let path = format!("/audiences/{audience_id}/contacts/{email}");
+        let path = path("/audiences", &[audience_id, "contacts", email]);
 
         let request = self.0.build(Method::DELETE, &path);
         let response = self.0.send(request).await?;
-        let content = response.json::<types::DeleteContactResponse>().await?;
+        let content = decode::<types::DeleteContactResponse>(response).await?;
 
         Ok(content.deleted)
     }
@@ -97,14 +160,43 @@ impl ContactsSvc {
     ///
     /// <https://resend.com/docs/api-reference/contacts/list-contacts>
     #[maybe_async::maybe_async]
-    pub async fn list(&self, audience: &str) -> Result<Vec<Contact>> {
-        let path = format!("/audiences/{audience}/contacts");
+    pub async fn list(&self, audience: &str) -> Result<Page<Contact>> {
+        self.list_with(audience, ListContactsOptions::default())
+            .await
+    }
 
-        let request = self.0.build(Method::GET, &path);
+    /// Retrieves the contacts from an audience matching `options`.
+    ///
+    /// The API doesn't filter contacts itself, so filters are applied to the returned page.
+    ///
+    /// <https://resend.com/docs/api-reference/contacts/list-contacts>
+    #[maybe_async::maybe_async]
+    #[allow(clippy::needless_pass_by_value)]
+    pub async fn list_with(
+        &self,
+        audience: &str,
+        options: ListContactsOptions,
+    ) -> Result<Page<Contact>> {
+        let path = path("/audiences", &[audience, "contacts"]);
+
+        let mut request = self.0.build(Method::GET, &path);
+        if let Some(after) = &options.after {
+            request = request.query(&[("after", after.as_ref())]);
+        }
+        if let Some(before) = &options.before {
+            request = request.query(&[("before", before.as_ref())]);
+        }
         let response = self.0.send(request).await?;
-        let content = response.json::<types::ListContactResponse>().await?;
+        let content = decode::<Page<Contact>>(response).await?;
+
+        // Computed before filtering so that the cursor still points past the whole page.
+        let mut page = content.with_next_cursor(|contact| &contact.id);
+        if let Some(unsubscribed) = options.unsubscribed {
+            page.data
+                .retain(|contact| contact.unsubscribed == unsubscribed);
+        }
 
-        Ok(content.data)
+        Ok(page)
     }
 }
 
@@ -115,11 +207,14 @@ impl fmt::Debug for ContactsSvc {
 }
 
 pub mod types {
-    use std::{fmt, ops::Deref};
+    use std::collections::HashMap;
+    use std::{convert::Infallible, fmt, ops::Deref, str::FromStr};
 
     use ecow::EcoString;
     use serde::{Deserialize, Serialize};
 
+    use crate::types::Cursor;
+
     /// Unique [`Contact`] identifier.
     #[derive(Debug, Clone, Deserialize)]
     pub struct ContactId(EcoString);
@@ -148,6 +243,16 @@ pub mod types {
         }
     }
 
+    impl FromStr for ContactId {
+        type Err = Infallible;
+
+        /// Same as [`ContactId::new`], never fails.
+        #[inline]
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            Ok(Self::new(s))
+        }
+    }
+
     impl fmt::Display for ContactId {
         fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
             fmt::Display::fmt(self.as_ref(), f)
@@ -170,6 +275,9 @@ pub mod types {
         /// Indicates if the contact is unsubscribed.
         #[serde(skip_serializing_if = "Option::is_none")]
         pub unsubscribed: Option<bool>,
+        /// Custom key-value metadata of the contact.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub metadata: Option<HashMap<String, String>>,
     }
 
     impl ContactData {
@@ -180,6 +288,7 @@ pub mod types {
                 first_name: None,
                 last_name: None,
                 unsubscribed: None,
+                metadata: None,
             }
         }
 
@@ -203,6 +312,21 @@ pub mod types {
             self.unsubscribed = Some(unsubscribed);
             self
         }
+
+        /// Replaces the metadata of the contact.
+        #[inline]
+        pub fn with_metadata(mut self, metadata: HashMap<String, String>) -> Self {
+            self.metadata = Some(metadata);
+            self
+        }
+
+        /// Adds a single metadata entry to the contact.
+        #[inline]
+        pub fn with_metadata_entry(mut self, key: &str, value: &str) -> Self {
+            let metadata = self.metadata.get_or_insert_with(HashMap::new);
+            let _ = metadata.insert(key.to_owned(), value.to_owned());
+            self
+        }
     }
 
     #[derive(Debug, Clone, Deserialize)]
@@ -211,13 +335,6 @@ pub mod types {
         pub id: ContactId,
     }
 
-    #[must_use]
-    #[derive(Debug, Clone, Deserialize)]
-    pub struct ListContactResponse {
-        /// Array containing contact information.
-        pub data: Vec<Contact>,
-    }
-
     /// Details of an existing contact.
     #[must_use]
     #[derive(Debug, Clone, Deserialize)]
@@ -249,6 +366,9 @@ pub mod types {
         /// Indicates the subscription status of the contact.
         #[serde(skip_serializing_if = "Option::is_none")]
         pub unsubscribed: Option<bool>,
+        /// Custom key-value metadata of the contact.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub metadata: Option<HashMap<String, String>>,
     }
 
     impl ContactChanges {
@@ -278,6 +398,67 @@ pub mod types {
             self.unsubscribed = Some(unsubscribed);
             self
         }
+
+        /// Replaces the metadata of the contact.
+        #[inline]
+        pub fn with_metadata(mut self, metadata: HashMap<String, String>) -> Self {
+            self.metadata = Some(metadata);
+            self
+        }
+
+        /// Adds a single metadata entry to the contact.
+        #[inline]
+        pub fn with_metadata_entry(mut self, key: &str, value: &str) -> Self {
+            let metadata = self.metadata.get_or_insert_with(HashMap::new);
+            let _ = metadata.insert(key.to_owned(), value.to_owned());
+            self
+        }
+    }
+
+    /// Filters and pagination for [`ContactsSvc::list_with`].
+    ///
+    /// [`ContactsSvc::list_with`]: crate::services::ContactsSvc::list_with
+    #[must_use]
+    #[derive(Debug, Default, Clone)]
+    pub struct ListContactsOptions {
+        /// Only keeps contacts with this subscription status.
+        pub unsubscribed: Option<bool>,
+        /// Only lists contacts after this cursor.
+        pub after: Option<Cursor>,
+        /// Only lists contacts before this cursor.
+        pub before: Option<Cursor>,
+    }
+
+    impl ListContactsOptions {
+        /// Creates a new [`ListContactsOptions`] without any filter.
+        #[inline]
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        /// Only keeps contacts with this subscription status, or all contacts if `None`.
+        #[inline]
+        pub const fn unsubscribed(mut self, unsubscribed: Option<bool>) -> Self {
+            self.unsubscribed = unsubscribed;
+            self
+        }
+
+        /// Continues listing after `cursor`, usually the [`Page::next_cursor`] of the previous
+        /// page.
+        ///
+        /// [`Page::next_cursor`]: crate::types::Page::next_cursor
+        #[inline]
+        pub fn after(mut self, cursor: Cursor) -> Self {
+            self.after = Some(cursor);
+            self
+        }
+
+        /// Lists the contacts before `cursor`.
+        #[inline]
+        pub fn before(mut self, cursor: Cursor) -> Self {
+            self.before = Some(cursor);
+            self
+        }
     }
 
     #[derive(Debug, Clone, Deserialize)]
@@ -298,8 +479,17 @@ pub mod types {
 
 #[cfg(test)]
 mod test {
-    use crate::tests::CLIENT;
+    use std::collections::HashMap;
+
+    #[cfg(not(feature = "blocking"))]
+    use crate::tests::{
+        mock::{MockResponse, MockServer},
+        CLIENT,
+    };
+    #[cfg(not(feature = "blocking"))]
+    use crate::types::{AudienceId, ContactId, ListContactsOptions};
     use crate::types::{ContactChanges, ContactData};
+    #[cfg(not(feature = "blocking"))]
     use crate::{Resend, Result};
 
     #[tokio::test]
@@ -321,15 +511,15 @@ mod test {
 
         // Update.
         let changes = ContactChanges::new().with_unsubscribed(true);
-        let _res = resend.contacts.update(&id, &audience_id, changes).await?;
+        let _res = resend.contacts.update(&audience_id, &id, changes).await?;
 
         // Retrieve.
-        let contact = resend.contacts.get(&id, &audience_id).await?;
+        let contact = resend.contacts.get(&audience_id, &id).await?;
         assert!(contact.unsubscribed);
 
         // List.
         let contacts = resend.contacts.list(&audience_id).await?;
-        assert_eq!(contacts.len(), 1);
+        assert_eq!(contacts.data.len(), 1);
 
         // Delete.
         let _ = resend
@@ -338,11 +528,216 @@ mod test {
             .await?;
 
         // Delete audience.
-        let _ = resend.audiences.delete(&audience_id).await?;
+        resend.audiences.delete(&audience_id).await?;
 
         // List.
         let contacts = resend.contacts.list(&audience_id).await?;
-        assert!(contacts.is_empty());
+        assert!(contacts.data.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn wire_names() -> serde_json::Result<()> {
+        let contact = ContactData::new("steve.wozniak@gmail.com")
+            .with_first_name("Steve")
+            .with_last_name("Wozniak")
+            .with_unsubscribed(false);
+        let expected = serde_json::json!({
+            "email": "steve.wozniak@gmail.com",
+            "first_name": "Steve",
+            "last_name": "Wozniak",
+            "unsubscribed": false
+        });
+        assert_eq!(serde_json::to_value(contact)?, expected);
+
+        let changes = ContactChanges::new()
+            .with_first_name("Steve")
+            .with_last_name("Wozniak")
+            .with_unsubscribed(true);
+        let expected = serde_json::json!({
+            "first_name": "Steve",
+            "last_name": "Wozniak",
+            "unsubscribed": true
+        });
+        assert_eq!(serde_json::to_value(changes)?, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn partial_changes() -> serde_json::Result<()> {
+        let changes = ContactChanges::new().with_unsubscribed(true);
+        let expected = serde_json::json!({ "unsubscribed": true });
+        assert_eq!(serde_json::to_value(changes)?, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn metadata_only_when_set() -> serde_json::Result<()> {
+        let contact = ContactData::new("steve.wozniak@gmail.com");
+        let expected = serde_json::json!({ "email": "steve.wozniak@gmail.com" });
+        assert_eq!(serde_json::to_value(contact)?, expected);
+
+        let contact = ContactData::new("steve.wozniak@gmail.com")
+            .with_metadata_entry("plan", "pro")
+            .with_metadata_entry("region", "eu");
+        let expected = serde_json::json!({
+            "email": "steve.wozniak@gmail.com",
+            "metadata": { "plan": "pro", "region": "eu" }
+        });
+        assert_eq!(serde_json::to_value(contact)?, expected);
+
+        assert_eq!(
+            serde_json::to_value(ContactChanges::new())?,
+            serde_json::json!({})
+        );
+
+        let metadata = HashMap::from([("plan".to_owned(), "free".to_owned())]);
+        let changes = ContactChanges::new().with_metadata(metadata);
+        let expected = serde_json::json!({ "metadata": { "plan": "free" } });
+        assert_eq!(serde_json::to_value(changes)?, expected);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[cfg(not(feature = "blocking"))]
+    async fn list_subscribed() -> Result<()> {
+        let server = MockServer::start(|_| {
+            let contact = |id: &str, unsubscribed: bool| {
+                serde_json::json!({
+                    "id": id,
+                    "email": format!("{id}@a.dev"),
+                    "first_name": "",
+                    "last_name": "",
+                    "created_at": "2023-10-06T23:47:56.678Z",
+                    "unsubscribed": unsubscribed
+                })
+            };
+            let data = [contact("1", false), contact("2", true), contact("3", false)];
+            let body = serde_json::json!({ "object": "list", "data": data });
+            MockResponse::json(200, &body.to_string())
+        });
+        let resend = Resend::builder("re_123").base_url(server.url()).build()?;
+
+        let options = ListContactsOptions::new().unsubscribed(Some(false));
+        let page = resend.contacts.list_with("audience", options).await?;
+        let ids = page.iter().map(|c| c.id.as_ref()).collect::<Vec<_>>();
+        assert_eq!(ids, ["1", "3"]);
+
+        let page = resend.contacts.list("audience").await?;
+        assert_eq!(page.len(), 3);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[cfg(not(feature = "blocking"))]
+    async fn list_next_page() -> Result<()> {
+        let server = MockServer::start(|request| {
+            let (id, has_more) = if request.path.contains("after=") {
+                ("2", false)
+            } else {
+                ("1", true)
+            };
+            let body = serde_json::json!({
+                "object": "list",
+                "has_more": has_more,
+                "data": [{
+                    "id": id,
+                    "email": "a@a.dev",
+                    "first_name": "",
+                    "last_name": "",
+                    "created_at": "2023-10-06T23:47:56.678Z",
+                    "unsubscribed": false
+                }]
+            });
+            MockResponse::json(200, &body.to_string())
+        });
+        let resend = Resend::builder("re_123").base_url(server.url()).build()?;
+
+        let page = resend.contacts.list("audience").await?;
+        let cursor = page
+            .next_cursor
+            .expect("first page should have a next cursor");
+        assert_eq!(cursor.as_ref(), "1");
+
+        let options = ListContactsOptions::new().after(cursor);
+        let page = resend.contacts.list_with("audience", options).await?;
+        assert_eq!(page.data[0].id.as_ref(), "2");
+        assert!(page.next_cursor.is_none());
+
+        let requests = server.requests();
+        assert_eq!(requests[1].path, "/audiences/audience/contacts?after=1");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[cfg(not(feature = "blocking"))]
+    async fn upsert_existing() -> Result<()> {
+        let server = MockServer::start(|request| {
+            if request.method == "POST" {
+                let body =
+                    r#"{"statusCode":409,"message":"Contact already exists","name":"conflict"}"#;
+                return MockResponse::json(409, body);
+            }
+            MockResponse::json(200, r#"{"object":"contact","id":"479e3145"}"#)
+        });
+        let resend = Resend::builder("re_123").base_url(server.url()).build()?;
+
+        let contact = ContactData::new("steve.wozniak@gmail.com").with_first_name("Steve");
+        let id = resend.contacts.upsert("audience", contact).await?;
+        assert_eq!(id.as_ref(), "479e3145");
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[1].method, "PATCH");
+        assert_eq!(
+            requests[1].path,
+            "/audiences/audience/contacts/steve.wozniak@gmail.com"
+        );
+        assert_eq!(requests[1].body_str(), r#"{"first_name":"Steve"}"#);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[cfg(not(feature = "blocking"))]
+    async fn get_and_try_get() -> Result<()> {
+        let server = MockServer::start(|request| {
+            if request.path.ends_with("missing") {
+                let body = r#"{"statusCode":404,"message":"Contact not found","name":"not_found"}"#;
+                return MockResponse::json(404, body);
+            }
+            let body = r#"{
+                "object": "contact",
+                "id": "479e3145",
+                "email": "steve.wozniak@gmail.com",
+                "first_name": "Steve",
+                "last_name": "Wozniak",
+                "created_at": "2023-10-06T23:47:56.678Z",
+                "unsubscribed": false
+            }"#;
+            MockResponse::json(200, body)
+        });
+        let resend = Resend::builder("re_123").base_url(server.url()).build()?;
+
+        let audience_id = AudienceId::new("audience");
+        let contact_id = ContactId::new("479e3145");
+        let contact = resend.contacts.get(&audience_id, &contact_id).await?;
+        assert_eq!(contact.id.as_ref(), "479e3145");
+        assert!(resend
+            .contacts
+            .try_get(&audience_id, &ContactId::new("missing"))
+            .await?
+            .is_none());
+
+        let requests = server.requests();
+        assert_eq!(requests[0].path, "/audiences/audience/contacts/479e3145");
+        assert_eq!(requests[1].path, "/audiences/audience/contacts/missing");
 
         Ok(())
     }
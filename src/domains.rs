@@ -1,14 +1,20 @@
 use std::fmt;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use reqwest::Method;
 use types::DeleteDomainResponse;
 
-use crate::types::{CreateDomainOptions, Domain, DomainChanges};
+use crate::config::{decode, path, sleep};
+use crate::types::{CreateDomainOptions, Domain, DomainChanges, VerifyDomainResponse};
 use crate::{Config, Result};
 
 use self::types::UpdateDomainResponse;
 
+/// How many domains [`DomainsSvc::list_detailed`] retrieves at once.
+#[cfg(not(feature = "blocking"))]
+const LIST_DETAILED_CONCURRENCY: usize = 4;
+
 /// `Resend` APIs for `/domains` endpoints.
 #[derive(Clone)]
 pub struct DomainsSvc(pub(crate) Arc<Config>);
@@ -23,7 +29,7 @@ impl DomainsSvc {
     pub async fn add(&self, domain: CreateDomainOptions) -> Result<Domain> {
         let request = self.0.build(Method::POST, "/domains");
         let response = self.0.send(request.json(&domain)).await?;
-        let content = response.json::<Domain>().await?;
+        let content = decode::<Domain>(response).await?;
 
         Ok(content)
     }
@@ -33,27 +39,57 @@ impl DomainsSvc {
     /// <https://resend.com/docs/api-reference/domains/get-domain>
     #[maybe_async::maybe_async]
     pub async fn get(&self, domain_id: &str) -> Result<Domain> {
-        let path = format!("/domains/{domain_id}");
+        let path = path("/domains", &[domain_id]);
 
         let request = self.0.build(Method::GET, &path);
         let response = self.0.send(request).await?;
-        let content = response.json::<Domain>().await?;
+        let content = decode::<Domain>(response).await?;
 
         Ok(content)
     }
 
     /// Verifies an existing domain.
     ///
+    /// Use [`VerifyDomainResponse::failing_records`] to find out which records still fail.
+    ///
     /// <https://resend.com/docs/api-reference/domains/verify-domain>
     #[maybe_async::maybe_async]
-    pub async fn verify(&self, domain_id: &str) -> Result<()> {
-        let path = format!("/domains/{domain_id}/verify");
+    pub async fn verify(&self, domain_id: &str) -> Result<VerifyDomainResponse> {
+        let path = path("/domains", &[domain_id, "verify"]);
 
         let request = self.0.build(Method::POST, &path);
         let response = self.0.send(request).await?;
-        let _content = response.json::<types::VerifyDomainResponse>().await?;
+        let content = decode::<VerifyDomainResponse>(response).await?;
 
-        Ok(())
+        Ok(content)
+    }
+
+    /// Triggers a verification with [`DomainsSvc::verify`], then polls [`DomainsSvc::get`] every
+    /// `interval` until the domain is `verified`, or until `timeout` has elapsed.
+    ///
+    /// Returns the last retrieved [`Domain`] in both cases, check its `status` to tell them
+    /// apart. DNS changes can take a while to propagate so prefer generous timeouts.
+    ///
+    /// <https://resend.com/docs/api-reference/domains/verify-domain>
+    #[maybe_async::maybe_async]
+    pub async fn verify_and_wait(
+        &self,
+        domain_id: &str,
+        timeout: Duration,
+        interval: Duration,
+    ) -> Result<Domain> {
+        let start = Instant::now();
+        let _ = self.verify(domain_id).await?;
+
+        loop {
+            let domain = self.get(domain_id).await?;
+
+            if domain.status.is_verified() || start.elapsed() + interval > timeout {
+                return Ok(domain);
+            }
+
+            sleep(interval).await;
+        }
     }
 
     /// Updates an existing domain.
@@ -65,11 +101,11 @@ impl DomainsSvc {
         domain_id: &str,
         update: DomainChanges,
     ) -> Result<UpdateDomainResponse> {
-        let path = format!("/domains/{domain_id}");
+        let path = path("/domains", &[domain_id]);
 
         let request = self.0.build(Method::PATCH, &path);
         let response = self.0.send(request.json(&update)).await?;
-        let content = response.json::<UpdateDomainResponse>().await?;
+        let content = decode::<UpdateDomainResponse>(response).await?;
 
         Ok(content)
     }
@@ -81,11 +117,44 @@ impl DomainsSvc {
     pub async fn list(&self) -> Result<Vec<Domain>> {
         let request = self.0.build(Method::GET, "/domains");
         let response = self.0.send(request).await?;
-        let content = response.json::<types::ListDomainResponse>().await?;
+        let content = decode::<types::ListDomainResponse>(response).await?;
 
         Ok(content.data)
     }
 
+    /// Same as [`DomainsSvc::list`] but also retrieves each domain with [`DomainsSvc::get`] so
+    /// that their `records` are included.
+    ///
+    /// The domains are retrieved with up to 4 requests in flight, through the client's rate
+    /// limiter.
+    ///
+    /// <https://resend.com/docs/api-reference/domains/list-domains>
+    #[cfg(not(feature = "blocking"))]
+    pub async fn list_detailed(&self) -> Result<Vec<Domain>> {
+        use futures_util::{stream, StreamExt, TryStreamExt};
+
+        let domains = self.list().await?;
+
+        stream::iter(&domains)
+            .map(|domain| self.get(&domain.id))
+            .buffered(LIST_DETAILED_CONCURRENCY)
+            .try_collect()
+            .await
+    }
+
+    /// Same as [`DomainsSvc::list`] but also retrieves each domain with [`DomainsSvc::get`] so
+    /// that their `records` are included.
+    ///
+    /// The domains are retrieved one after another through the client's rate limiter.
+    ///
+    /// <https://resend.com/docs/api-reference/domains/list-domains>
+    #[cfg(feature = "blocking")]
+    pub fn list_detailed(&self) -> Result<Vec<Domain>> {
+        let domains = self.list()?;
+
+        domains.iter().map(|domain| self.get(&domain.id)).collect()
+    }
+
     /// Removes an existing domain.
     ///
     /// Returns whether the domain was deleted successfully.
@@ -93,11 +162,11 @@ impl DomainsSvc {
     /// <https://resend.com/docs/api-reference/domains/delete-domain>
     #[maybe_async::maybe_async]
     pub async fn delete(&self, domain_id: &str) -> Result<DeleteDomainResponse> {
-        let path = format!("/domains/{domain_id}");
+        let path = path("/domains", &[domain_id]);
 
         let request = self.0.build(Method::DELETE, &path);
         let response = self.0.send(request).await?;
-        let content = response.json::<DeleteDomainResponse>().await?;
+        let content = decode::<DeleteDomainResponse>(response).await?;
 
         Ok(content)
     }
@@ -110,7 +179,7 @@ impl fmt::Debug for DomainsSvc {
 }
 
 pub mod types {
-    use std::{fmt, ops::Deref};
+    use std::{convert::Infallible, fmt, ops::Deref, str::FromStr};
 
     use ecow::EcoString;
     use serde::{Deserialize, Serialize};
@@ -138,6 +207,19 @@ pub mod types {
         pub fn new(id: &str) -> Self {
             Self(EcoString::from(id))
         }
+
+        /// Creates a new [`DomainId`], checking that `id` is a valid UUID.
+        ///
+        /// Unlike [`DomainId::new`], this catches obviously wrong ids before they reach the API.
+        #[cfg(feature = "uuid")]
+        pub fn try_new(id: &str) -> Result<Self, crate::types::IdParseError> {
+            uuid::Uuid::try_parse(id)
+                .map(|_| Self::new(id))
+                .map_err(|source| crate::types::IdParseError {
+                    id: id.to_owned(),
+                    source,
+                })
+        }
     }
 
     impl Deref for DomainId {
@@ -155,6 +237,16 @@ pub mod types {
         }
     }
 
+    impl FromStr for DomainId {
+        type Err = Infallible;
+
+        /// Same as [`DomainId::new`], never fails.
+        #[inline]
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            Ok(Self::new(s))
+        }
+    }
+
     impl fmt::Display for DomainId {
         fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
             fmt::Display::fmt(self.as_ref(), f)
@@ -173,6 +265,9 @@ pub mod types {
         /// Possible values are 'us-east-1' | 'eu-west-1' | 'sa-east-1'.
         #[serde(rename = "region", skip_serializing_if = "Option::is_none")]
         pub region: Option<Region>,
+        /// Subdomain used for the `Return-Path` address, defaults to `send`.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub custom_return_path: Option<String>,
     }
 
     impl CreateDomainOptions {
@@ -181,6 +276,7 @@ pub mod types {
             Self {
                 name: name.to_owned(),
                 region: None,
+                custom_return_path: None,
             }
         }
 
@@ -189,6 +285,14 @@ pub mod types {
             self.region = Some(region.into());
             self
         }
+
+        /// Sets the subdomain used for the `Return-Path` address, e.g. `outbound` for
+        /// `outbound.example.com`.
+        #[inline]
+        pub fn with_custom_return_path(mut self, custom_return_path: &str) -> Self {
+            self.custom_return_path = Some(custom_return_path.to_owned());
+            self
+        }
     }
 
     /// Region where [`CreateEmailBaseOptions`]s will be sent from.
@@ -226,6 +330,8 @@ pub mod types {
         pub ttl: String,
         /// The status of the record.
         pub status: DomainStatus,
+        /// Why the record failed verification, e.g. `SPF record not found`.
+        pub error: Option<String>,
 
         pub routing_policy: Option<String>,
         pub priority: Option<i32>,
@@ -245,6 +351,8 @@ pub mod types {
         pub ttl: String,
         /// The status of the record.
         pub status: DomainStatus,
+        /// Why the record failed verification, e.g. `DKIM record not found`.
+        pub error: Option<String>,
 
         pub routing_policy: Option<String>,
         pub priority: Option<i32>,
@@ -257,15 +365,29 @@ pub mod types {
         Disable,
     }
 
-    #[derive(Debug, Copy, Clone, Deserialize)]
+    /// Verification status of a [`Domain`] or of one of its records.
+    #[non_exhaustive]
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, Deserialize)]
+    #[serde(rename_all = "snake_case")]
     pub enum DomainStatus {
         Pending,
         Verified,
+        PartiallyVerified,
         Failed,
-        #[serde(rename = "temporary_failure")]
+        PartiallyFailed,
         TemporaryFailure,
-        #[serde(rename = "not_started")]
         NotStarted,
+        /// Status not known to this version of the crate.
+        #[serde(other)]
+        Unknown,
+    }
+
+    impl DomainStatus {
+        /// Returns whether this is [`DomainStatus::Verified`].
+        #[must_use]
+        pub const fn is_verified(self) -> bool {
+            matches!(self, Self::Verified)
+        }
     }
 
     #[derive(Debug, Copy, Clone, Deserialize)]
@@ -293,6 +415,37 @@ pub mod types {
         DomainDkimRecord(DomainDkimRecord),
     }
 
+    impl DomainRecord {
+        /// Returns the verification status of the record.
+        #[must_use]
+        pub const fn status(&self) -> DomainStatus {
+            match self {
+                Self::DomainSpfRecord(record) => record.status,
+                Self::DomainDkimRecord(record) => record.status,
+            }
+        }
+
+        /// Returns why the record failed verification, if the API reported a reason.
+        #[must_use]
+        pub fn error(&self) -> Option<&str> {
+            match self {
+                Self::DomainSpfRecord(record) => record.error.as_deref(),
+                Self::DomainDkimRecord(record) => record.error.as_deref(),
+            }
+        }
+
+        /// Returns whether the record failed verification.
+        #[must_use]
+        pub fn is_failing(&self) -> bool {
+            matches!(
+                self.status(),
+                DomainStatus::Failed
+                    | DomainStatus::PartiallyFailed
+                    | DomainStatus::TemporaryFailure
+            ) || self.error().is_some()
+        }
+    }
+
     /// Details of an existing domain.
     #[must_use]
     #[derive(Debug, Clone, Deserialize)]
@@ -301,9 +454,8 @@ pub mod types {
         pub id: DomainId,
         /// The name of the domain.
         pub name: String,
-        // TODO: Technically both this and the domainrecord could be an enum https://resend.com/docs/api-reference/domains/get-domain#path-parameters
         /// The status of the domain.
-        pub status: String,
+        pub status: DomainStatus,
 
         /// The date and time the domain was created in ISO8601 format.
         pub created_at: String,
@@ -313,11 +465,24 @@ pub mod types {
         pub records: Option<Vec<DomainRecord>>,
     }
 
+    /// Result of a [`Domain`] verification.
+    #[must_use]
     #[derive(Debug, Clone, Deserialize)]
     pub struct VerifyDomainResponse {
         /// The ID of the domain.
-        #[allow(dead_code)]
         pub id: DomainId,
+        /// The records of the domain, when included in the response.
+        pub records: Option<Vec<DomainRecord>>,
+    }
+
+    impl VerifyDomainResponse {
+        /// Returns the records that failed verification, see [`DomainRecord::error`] for why.
+        pub fn failing_records(&self) -> impl Iterator<Item = &DomainRecord> {
+            self.records
+                .iter()
+                .flatten()
+                .filter(|record| record.is_failing())
+        }
     }
 
     /// List of changes to apply to a [`Domain`].
@@ -387,10 +552,11 @@ pub mod types {
 #[cfg(test)]
 mod test {
     use crate::{
-        domains::types::{CreateDomainOptions, DomainChanges, Tls},
-        tests::CLIENT,
-        Resend, Result,
+        domains::types::{CreateDomainOptions, DomainChanges, Region, Tls},
+        types::{DomainStatus, VerifyDomainResponse},
     };
+    #[cfg(not(feature = "blocking"))]
+    use crate::{tests::CLIENT, Resend, Result};
 
     #[tokio::test]
     #[cfg(not(feature = "blocking"))]
@@ -430,4 +596,260 @@ mod test {
 
         Ok(())
     }
+
+    #[test]
+    fn domain_status() -> serde_json::Result<()> {
+        let statuses = [
+            ("not_started", DomainStatus::NotStarted),
+            ("pending", DomainStatus::Pending),
+            ("verified", DomainStatus::Verified),
+            ("partially_verified", DomainStatus::PartiallyVerified),
+            ("failed", DomainStatus::Failed),
+            ("partially_failed", DomainStatus::PartiallyFailed),
+            ("temporary_failure", DomainStatus::TemporaryFailure),
+            ("archived", DomainStatus::Unknown),
+        ];
+
+        for (name, expected) in statuses {
+            let status: DomainStatus = serde_json::from_value(serde_json::json!(name))?;
+            assert_eq!(status, expected);
+            assert_eq!(status.is_verified(), name == "verified");
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn wire_names() -> serde_json::Result<()> {
+        let domain = CreateDomainOptions::new("example.com").with_region(Region::EuWest1);
+        let expected = serde_json::json!({ "name": "example.com", "region": "eu-west-1" });
+        assert_eq!(serde_json::to_value(domain)?, expected);
+
+        let changes = DomainChanges::new()
+            .with_open_tracking(false)
+            .with_click_tracking(true)
+            .with_tls(Tls::Enforced);
+        let expected = serde_json::json!({
+            "open_tracking": false,
+            "click_tracking": true,
+            "tls": "enforced"
+        });
+        assert_eq!(serde_json::to_value(changes)?, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn custom_return_path() -> serde_json::Result<()> {
+        let domain = CreateDomainOptions::new("example.com");
+        let expected = serde_json::json!({ "name": "example.com" });
+        assert_eq!(serde_json::to_value(domain)?, expected);
+
+        let domain = CreateDomainOptions::new("example.com").with_custom_return_path("outbound");
+        let expected = serde_json::json!({
+            "name": "example.com",
+            "custom_return_path": "outbound"
+        });
+        assert_eq!(serde_json::to_value(domain)?, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn failing_records() -> serde_json::Result<()> {
+        let response = r#"{
+            "object": "domain",
+            "id": "d91cd9bd-1176-453e-8fc1-35364d380206",
+            "records": [
+                {
+                    "record": "SPF",
+                    "name": "send",
+                    "type": "MX",
+                    "ttl": "Auto",
+                    "status": "temporary_failure",
+                    "value": "feedback-smtp.us-east-1.amazonses.com",
+                    "priority": 10,
+                    "error": "SPF record not found"
+                },
+                {
+                    "record": "DKIM",
+                    "name": "resend._domainkey",
+                    "value": "p=MIGfMA0GCSqGSIb3DQEBAQUAA4GNADCBiQKBgQDsc4Lh8xilsngyKEgN2S84+21gn+x6SEXtjWvPiAAmnmggr5FWG42WnqczpzQ/mNblqHz4CDwUum6LtY6SdoOlDmrhvp5khA3cd661W9FlK3yp7+jVACQElS7d9O6jv8VsBbVg4COess3gyLE5RyxqF1vYsrEXqyM8TBz1n5AGkQIDAQA2",
+                    "type": "TXT",
+                    "status": "not_started",
+                    "ttl": "Auto"
+                }
+            ]
+        }"#;
+        let verify = serde_json::from_str::<VerifyDomainResponse>(response)?;
+
+        let failing = verify.failing_records().collect::<Vec<_>>();
+        assert_eq!(failing.len(), 1);
+        assert_eq!(failing[0].status(), DomainStatus::TemporaryFailure);
+        assert_eq!(failing[0].error(), Some("SPF record not found"));
+
+        // The API usually only returns the id.
+        let verify = serde_json::from_str::<VerifyDomainResponse>(
+            r#"{"object":"domain","id":"d91cd9bd-1176-453e-8fc1-35364d380206"}"#,
+        )?;
+        assert_eq!(verify.failing_records().count(), 0);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[cfg(not(feature = "blocking"))]
+    async fn list_detailed() -> Result<()> {
+        use crate::tests::mock::{MockResponse, MockServer};
+
+        let server = MockServer::start(|request| {
+            let domain = |id: &str, records: Option<serde_json::Value>| {
+                serde_json::json!({
+                    "id": id,
+                    "name": format!("{id}.dev"),
+                    "status": "verified",
+                    "created_at": "2023-04-26T20:21:26.347412+00:00",
+                    "region": "us-east-1",
+                    "records": records
+                })
+            };
+
+            let body = match request.path.as_str() {
+                "/domains" => serde_json::json!({ "data": [domain("a", None), domain("b", None)] }),
+                path => {
+                    let id = path.trim_start_matches("/domains/");
+                    domain(id, Some(serde_json::json!([])))
+                }
+            };
+            MockResponse::json(200, &body.to_string())
+        });
+        let resend = Resend::builder("re_123").base_url(server.url()).build()?;
+
+        let domains = resend.domains.list_detailed().await?;
+        let ids = domains.iter().map(|d| d.id.as_ref()).collect::<Vec<_>>();
+        assert_eq!(ids, ["a", "b"]);
+        assert!(domains.iter().all(|domain| domain.records.is_some()));
+
+        let mut paths = server
+            .requests()
+            .into_iter()
+            .map(|request| request.path)
+            .collect::<Vec<_>>();
+        paths.sort();
+        assert_eq!(paths, ["/domains", "/domains/a", "/domains/b"]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[cfg(not(feature = "blocking"))]
+    async fn list_detailed_bounded() -> Result<()> {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+        use std::time::Duration;
+
+        use super::LIST_DETAILED_CONCURRENCY;
+        use crate::tests::mock::{MockResponse, MockServer};
+
+        let in_flight = AtomicUsize::new(0);
+        let max_in_flight = Arc::new(AtomicUsize::new(0));
+        let max = max_in_flight.clone();
+        let server = MockServer::start(move |request| {
+            let domain = |id: &str| {
+                serde_json::json!({
+                    "id": id,
+                    "name": format!("{id}.dev"),
+                    "status": "verified",
+                    "created_at": "2023-04-26T20:21:26.347412+00:00",
+                    "region": "us-east-1"
+                })
+            };
+
+            if request.path == "/domains" {
+                let data = (0..12).map(|i| domain(&i.to_string())).collect::<Vec<_>>();
+                return MockResponse::json(200, &serde_json::json!({ "data": data }).to_string());
+            }
+
+            let others = in_flight.fetch_add(1, Ordering::SeqCst);
+            let _ = max.fetch_max(others + 1, Ordering::SeqCst);
+            std::thread::sleep(Duration::from_millis(50));
+            let _ = in_flight.fetch_sub(1, Ordering::SeqCst);
+
+            let id = request.path.trim_start_matches("/domains/");
+            MockResponse::json(200, &domain(id).to_string())
+        });
+        let resend = Resend::builder("re_123")
+            .base_url(server.url())
+            .rate_limit(false)
+            .build()?;
+
+        let domains = resend.domains.list_detailed().await?;
+        assert_eq!(domains.len(), 12);
+        assert!(max_in_flight.load(Ordering::SeqCst) <= LIST_DETAILED_CONCURRENCY);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[cfg(not(feature = "blocking"))]
+    async fn verify_and_wait() -> Result<()> {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::time::Duration;
+
+        use crate::tests::mock::{MockResponse, MockServer};
+
+        let polls = AtomicUsize::new(0);
+        let server = MockServer::start(move |request| {
+            if request.method == "POST" {
+                return MockResponse::json(200, r#"{"object":"domain","id":"d91cd9bd"}"#);
+            }
+
+            let status = match polls.fetch_add(1, Ordering::SeqCst) {
+                0 => "pending",
+                _ => "verified",
+            };
+            let domain = serde_json::json!({
+                "object": "domain",
+                "id": "d91cd9bd",
+                "name": "example.com",
+                "status": status,
+                "created_at": "2023-04-26T20:21:26.347412+00:00",
+                "region": "us-east-1"
+            });
+            MockResponse::json(200, &domain.to_string())
+        });
+        let resend = Resend::builder("re_123").base_url(server.url()).build()?;
+
+        let (timeout, interval) = (Duration::from_secs(5), Duration::from_millis(10));
+        let domain = resend
+            .domains
+            .verify_and_wait("d91cd9bd", timeout, interval)
+            .await?;
+        assert_eq!(domain.status, DomainStatus::Verified);
+
+        let requests = server.requests();
+        let paths = requests
+            .iter()
+            .map(|request| (request.method.as_str(), request.path.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            paths,
+            [
+                ("POST", "/domains/d91cd9bd/verify"),
+                ("GET", "/domains/d91cd9bd"),
+                ("GET", "/domains/d91cd9bd"),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "uuid")]
+    fn try_new_id() {
+        use crate::types::DomainId;
+
+        assert!(DomainId::try_new("4dd369bc-aa82-4ff3-97de-514ae3000ee0").is_ok());
+        assert!(DomainId::try_new("example.com").is_err());
+    }
 }
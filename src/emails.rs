@@ -1,55 +1,476 @@
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-use reqwest::Method;
+#[cfg(feature = "blocking")]
+use reqwest::blocking::{RequestBuilder, Response};
+use reqwest::header::CONTENT_TYPE;
+use reqwest::{Method, Url};
+#[cfg(not(feature = "blocking"))]
+use reqwest::{RequestBuilder, Response};
+use serde::Serialize;
 
-use crate::types::{CreateEmailBaseOptions, CreateEmailResponse, Email};
-use crate::{Config, Result};
+use crate::config::{decode, path, sleep};
+use crate::rate_limit::AdaptiveConcurrency;
+use crate::services::BatchSvc;
+use crate::types::{
+    CreateEmailBaseOptions, CreateEmailResponse, Email, EmailEvent, EmailId, RateLimitInfo,
+};
+use crate::{Config, Error, Result};
+
+/// Maximum amount of `to` recipients of an email.
+pub const MAX_RECIPIENTS: usize = 50;
+
+/// Maximum total size of the content of an email's attachments, in bytes.
+///
+/// Attachments hosted at a path are not counted.
+pub const MAX_ATTACHMENT_BYTES: usize = 40 * 1024 * 1024;
+
+/// How many times [`EmailsSvc::send_many`] retries a rate limited email.
+const MAX_SEND_RETRIES: u32 = 5;
 
 /// `Resend` APIs for `/emails` endpoints.
 #[derive(Clone, Debug)]
 pub struct EmailsSvc(pub(crate) Arc<Config>);
 
 impl EmailsSvc {
+    /// Returns a copy of this service that sends its requests to `base_url` instead.
+    ///
+    /// The copy shares the HTTP client and the rate limiter with the original service, which keeps
+    /// using the configured `base URL`. Useful to route some emails to a different region endpoint.
+    #[must_use]
+    pub fn with_base_url(&self, base_url: Url) -> Self {
+        let mut config = (*self.0).clone();
+        config.base_url = base_url;
+
+        Self(Arc::new(config))
+    }
+
     /// Start sending emails through the `Resend` Email API.
     ///
+    /// The email is [validated](CreateEmailBaseOptions::validate) first, invalid emails fail
+    /// right away without waiting on the rate limiter.
+    ///
     /// <https://resend.com/docs/api-reference/emails/send-email>
     #[maybe_async::maybe_async]
     // Reasoning for allow: https://github.com/resend/resend-rust/pull/1#issuecomment-2081646115
     #[allow(clippy::needless_pass_by_value)]
     pub async fn send(&self, email: CreateEmailBaseOptions) -> Result<CreateEmailResponse> {
+        self.send_ref(&email).await
+    }
+
+    /// Same as [`EmailsSvc::send`] but borrows the email, so that a template can be sent
+    /// repeatedly without being cloned.
+    ///
+    /// <https://resend.com/docs/api-reference/emails/send-email>
+    #[maybe_async::maybe_async]
+    pub async fn send_ref(&self, email: &CreateEmailBaseOptions) -> Result<CreateEmailResponse> {
+        let response = self.send_raw(email).await?;
+        let content = decode::<CreateEmailResponse>(response).await?;
+
+        Ok(content)
+    }
+
+    /// Same as [`EmailsSvc::send`] but only returns the ID of the sent email, dropping any
+    /// warnings.
+    ///
+    /// <https://resend.com/docs/api-reference/emails/send-email>
+    #[maybe_async::maybe_async]
+    #[allow(clippy::needless_pass_by_value)]
+    pub async fn send_id(&self, email: CreateEmailBaseOptions) -> Result<EmailId> {
+        Ok(self.send_ref(&email).await?.id)
+    }
+
+    /// Sends `emails` with up to `concurrency` requests in flight, one request per email.
+    ///
+    /// Results are returned in the same order as `emails`. All requests go through the client's
+    /// rate limiter, so this never exceeds `RESEND_RATE_LIMIT`.
+    ///
+    /// Rate limited (`429`) emails are retried up to 5 times, after the `retry-after` (or else the
+    /// `ratelimit-reset`) of the response or an exponential backoff. Every `429` also halves the
+    /// amount of requests sent concurrently, which grows back by one with every success, so that
+    /// large jobs slow down instead of running into more `429`s.
+    ///
+    /// <https://resend.com/docs/api-reference/emails/send-email>
+    #[cfg(not(feature = "blocking"))]
+    pub async fn send_many(
+        &self,
+        emails: &[CreateEmailBaseOptions],
+        concurrency: usize,
+    ) -> Vec<Result<CreateEmailResponse>> {
+        use futures_util::{stream, StreamExt};
+
+        let limit = AdaptiveConcurrency::new(concurrency);
+        let limit = &limit;
+
+        stream::iter(emails)
+            .map(|email| self.send_adaptive(email, limit))
+            .buffered(concurrency.max(1))
+            .collect()
+            .await
+    }
+
+    /// Sends `emails` concurrently over up to `threads` threads, one request per email.
+    ///
+    /// Results are returned in the same order as `emails`. All threads share the client's rate
+    /// limiter, so this never exceeds `RESEND_RATE_LIMIT`.
+    ///
+    /// Rate limited (`429`) emails are retried up to 5 times, after the `retry-after` (or else the
+    /// `ratelimit-reset`) of the response or an exponential backoff. Every `429` also halves the
+    /// amount of requests sent concurrently, which grows back by one with every success, so that
+    /// large jobs slow down instead of running into more `429`s.
+    ///
+    /// <https://resend.com/docs/api-reference/emails/send-email>
+    #[cfg(feature = "blocking")]
+    pub fn send_many(
+        &self,
+        emails: &[CreateEmailBaseOptions],
+        threads: usize,
+    ) -> Vec<Result<CreateEmailResponse>> {
+        if emails.is_empty() {
+            return Vec::new();
+        }
+
+        // Contiguous chunks per thread keep the results trivially ordered.
+        let chunk_size = emails.len().div_ceil(threads.max(1));
+        let concurrency = AdaptiveConcurrency::new(threads);
+        let concurrency = &concurrency;
+
+        std::thread::scope(|scope| {
+            // Collected so that every thread is spawned before the first one is joined.
+            #[allow(clippy::needless_collect)]
+            let handles = emails
+                .chunks(chunk_size)
+                .map(|chunk| {
+                    scope.spawn(move || {
+                        chunk
+                            .iter()
+                            .map(|email| self.send_adaptive(email, concurrency))
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            handles
+                .into_iter()
+                .flat_map(|handle| {
+                    handle
+                        .join()
+                        .unwrap_or_else(|e| std::panic::resume_unwind(e))
+                })
+                .collect()
+        })
+    }
+
+    /// Sends `email` through `concurrency`, retrying with a backoff if it is rate limited.
+    #[maybe_async::maybe_async]
+    async fn send_adaptive(
+        &self,
+        email: &CreateEmailBaseOptions,
+        concurrency: &AdaptiveConcurrency,
+    ) -> Result<CreateEmailResponse> {
+        let mut retries = 0;
+        loop {
+            let result = concurrency.run(|| self.send_ref(email)).await;
+
+            // Other errors don't say anything about the rate limit, keep the concurrency as is.
+            let rate_limit = match &result {
+                Ok(_) => None,
+                Err(Error::Resend(error)) if error.status_code == 429 => {
+                    Some(error.rate_limit.as_deref())
+                }
+                Err(_) => return result,
+            };
+            concurrency.adapt(rate_limit.is_some());
+
+            match rate_limit {
+                Some(info) if retries < MAX_SEND_RETRIES => {
+                    sleep(backoff(retries, info)).await;
+                    retries += 1;
+                }
+                _ => return result,
+            }
+        }
+    }
+
+    /// Same as [`EmailsSvc::send_ref`] but skips the queue of regular sends, for urgent emails such
+    /// as password resets that shouldn't wait behind bulk sends.
+    ///
+    /// One request per window of `RESEND_RATE_LIMIT` is reserved for priority sends, regular sends
+    /// share the rest (8 per window by default), so both lanes together never exceed
+    /// `RESEND_RATE_LIMIT`. With a limit of 1 there is nothing to reserve, priority sends then
+    /// wait in the same queue as regular ones.
+    ///
+    /// <https://resend.com/docs/api-reference/emails/send-email>
+    #[maybe_async::maybe_async]
+    pub async fn send_priority(
+        &self,
+        email: &CreateEmailBaseOptions,
+    ) -> Result<CreateEmailResponse> {
+        email.validate()?;
+        let email = email.with_default_tags(&self.0.default_tags);
+
+        let response = self.0.send_priority(self.request(&email)).await?;
+        let content = decode::<CreateEmailResponse>(response).await?;
+
+        Ok(content)
+    }
+
+    /// Same as [`EmailsSvc::send_ref`] but returns the undecoded [`Response`], e.g. to read its
+    /// headers or stream its body.
+    ///
+    /// Errors are still mapped, only successful responses are returned.
+    ///
+    /// <https://resend.com/docs/api-reference/emails/send-email>
+    #[maybe_async::maybe_async]
+    pub async fn send_raw(&self, email: &CreateEmailBaseOptions) -> Result<Response> {
+        email.validate()?;
+        let email = email.with_default_tags(&self.0.default_tags);
+
+        self.0.send(self.request(&email)).await
+    }
+
+    /// Sends any serializable `body` to the send email endpoint, e.g. a struct of your own with
+    /// fields this crate doesn't support yet.
+    ///
+    /// Unlike [`EmailsSvc::send`], the body is neither validated nor given the default tags, and
+    /// no idempotency key is derived for it.
+    ///
+    /// <https://resend.com/docs/api-reference/emails/send-email>
+    #[maybe_async::maybe_async]
+    pub async fn send_custom<T: Serialize + Sync>(&self, body: &T) -> Result<CreateEmailResponse> {
+        let request = self.0.build(Method::POST, "/emails").json(body);
+        let response = self.0.send(request).await?;
+        let content = decode::<CreateEmailResponse>(response).await?;
+
+        Ok(content)
+    }
+
+    /// Builds the request sending `email`, with a derived `Idempotency-Key` if enabled.
+    fn request(&self, email: &CreateEmailBaseOptions) -> RequestBuilder {
+        #[cfg(feature = "tracing")]
+        if self.0.warn_on_missing_text && email.html.is_some() && email.text.is_none() {
+            tracing::warn!(
+                subject = email.subject,
+                "sending an HTML-only email, add a text version to improve deliverability"
+            );
+        }
+
         let request = self.0.build(Method::POST, "/emails");
-        let response = self.0.send(request.json(&email)).await?;
-        let content = response.json::<CreateEmailResponse>().await?;
+        if !self.0.auto_idempotency {
+            return request.json(email);
+        }
+
+        // Serialized once for both the key and the body, so that attached files are read once.
+        match serde_json::to_vec(email) {
+            Ok(body) => request
+                .header(CONTENT_TYPE, "application/json")
+                .header("Idempotency-Key", idempotency_key(&body))
+                .body(body),
+            // Fails the same way once sent.
+            Err(_) => request.json(email),
+        }
+    }
+
+    /// Moves a scheduled email to `scheduled_at`.
+    ///
+    /// Same format as [`CreateEmailBaseOptions::with_scheduled_at`]. Fails with
+    /// [`Error::Resend`] carrying the API's explanation if the email can no longer be changed,
+    /// e.g. because it has already been sent.
+    ///
+    /// <https://resend.com/docs/api-reference/emails/update-email>
+    ///
+    /// [`Error::Resend`]: crate::Error::Resend
+    #[maybe_async::maybe_async]
+    pub async fn reschedule(
+        &self,
+        email_id: &str,
+        scheduled_at: &str,
+    ) -> Result<CreateEmailResponse> {
+        let path = path("/emails", &[email_id]);
+        let update = types::UpdateEmailOptions {
+            scheduled_at: scheduled_at.to_owned(),
+        };
+
+        let request = self.0.build(Method::PATCH, &path);
+        let response = self.0.send(request.json(&update)).await?;
+        let content = decode::<CreateEmailResponse>(response).await?;
 
         Ok(content)
     }
 
+    /// Cancels a scheduled email.
+    ///
+    /// <https://resend.com/docs/api-reference/emails/cancel-email>
+    #[maybe_async::maybe_async]
+    pub async fn cancel(&self, email_id: &str) -> Result<()> {
+        let path = path("/emails", &[email_id, "cancel"]);
+
+        let request = self.0.build(Method::POST, &path);
+        let _response = self.0.send(request).await?;
+
+        Ok(())
+    }
+
+    /// Cancels several scheduled emails one by one, e.g. the emails of a campaign, through the
+    /// rate limiter since the API has no bulk cancel.
+    ///
+    /// Returns one result per id, in the same order as `email_ids`.
+    ///
+    /// <https://resend.com/docs/api-reference/emails/cancel-email>
+    #[maybe_async::maybe_async]
+    pub async fn cancel_many(&self, email_ids: &[&str]) -> Vec<Result<()>> {
+        let mut results = Vec::with_capacity(email_ids.len());
+        for email_id in email_ids {
+            results.push(self.cancel(email_id).await);
+        }
+
+        results
+    }
+
     /// Retrieve a single email.
     ///
     /// <https://resend.com/docs/api-reference/emails/retrieve-email>
     #[maybe_async::maybe_async]
     pub async fn get(&self, email_id: &str) -> Result<Email> {
-        let path = format!("/emails/{email_id}");
+        let path = path("/emails", &[email_id]);
 
         let request = self.0.build(Method::GET, &path);
         let response = self.0.send(request).await?;
         // dbg!(response.text().await);
         // todo!();
-        let content = response.json::<Email>().await?;
+        let content = decode::<Email>(response).await?;
 
         Ok(content)
     }
+
+    /// Same as [`EmailsSvc::get`] but returns `None` if the email doesn't exist.
+    ///
+    /// <https://resend.com/docs/api-reference/emails/retrieve-email>
+    #[maybe_async::maybe_async]
+    pub async fn try_get(&self, email_id: &str) -> Result<Option<Email>> {
+        match self.get(email_id).await {
+            Ok(email) => Ok(Some(email)),
+            Err(e) if e.is_not_found() => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Polls [`EmailsSvc::get`] every `interval` until the email's `last_event` is `target`, or
+    /// until `timeout` has elapsed.
+    ///
+    /// Returns the last retrieved [`Email`] in both cases, check its `last_event` to tell them
+    /// apart. Polling goes through the client's rate limiter like any other request.
+    ///
+    /// <https://resend.com/docs/api-reference/emails/retrieve-email>
+    #[maybe_async::maybe_async]
+    pub async fn wait_for_event(
+        &self,
+        email_id: &str,
+        target: EmailEvent,
+        timeout: Duration,
+        interval: Duration,
+    ) -> Result<Email> {
+        let start = Instant::now();
+
+        loop {
+            let email = self.get(email_id).await?;
+
+            if email.last_event == target.as_str() || start.elapsed() + interval > timeout {
+                return Ok(email);
+            }
+
+            sleep(interval).await;
+        }
+    }
+}
+
+/// Common surface of the `/emails` endpoints.
+///
+/// Implemented by [`EmailsSvc`], and by [`MockClient`] when the `test-util` feature is enabled,
+/// so that code sending emails can be tested without a live server.
+///
+/// The trait is object safe, handlers can accept a `&dyn SendEmails` and have a fake
+/// implementation swapped in during tests.
+///
+/// [`MockClient`]: crate::test_util::MockClient
+#[maybe_async::maybe_async]
+pub trait SendEmails: Send + Sync {
+    /// Sends a single email, see [`EmailsSvc::send`].
+    async fn send(&self, email: CreateEmailBaseOptions) -> Result<CreateEmailResponse>;
+
+    /// Sends a batch of emails, see [`BatchSvc::send`].
+    ///
+    /// [`BatchSvc::send`]: crate::services::BatchSvc::send
+    async fn send_batch(
+        &self,
+        emails: Vec<CreateEmailBaseOptions>,
+    ) -> Result<Vec<CreateEmailResponse>>;
+
+    /// Retrieves a single email, see [`EmailsSvc::get`].
+    async fn get(&self, email_id: &str) -> Result<Email>;
+}
+
+#[maybe_async::maybe_async]
+impl SendEmails for EmailsSvc {
+    async fn send(&self, email: CreateEmailBaseOptions) -> Result<CreateEmailResponse> {
+        Self::send(self, email).await
+    }
+
+    async fn send_batch(
+        &self,
+        emails: Vec<CreateEmailBaseOptions>,
+    ) -> Result<Vec<CreateEmailResponse>> {
+        BatchSvc(self.0.clone()).send(emails).await
+    }
+
+    async fn get(&self, email_id: &str) -> Result<Email> {
+        Self::get(self, email_id).await
+    }
+}
+
+/// Returns an idempotency key derived from the serialized `body` of an email.
+///
+/// The hash is a 128-bit FNV-1a, so the same email always gets the same key, across processes and
+/// Rust releases.
+fn idempotency_key(body: &[u8]) -> String {
+    const OFFSET: u128 = 0x6c62_272e_07bb_0142_62b8_2175_6295_c58d;
+    const PRIME: u128 = 0x0000_0000_0100_0000_0000_0000_0000_013b;
+
+    let hash = body.iter().fold(OFFSET, |hash, byte| {
+        (hash ^ u128::from(*byte)).wrapping_mul(PRIME)
+    });
+    format!("auto-{hash:032x}")
+}
+
+/// How long to wait before retrying a rate limited email: the `retry-after` or `ratelimit-reset`
+/// of the response, or else an exponential backoff from 100ms.
+fn backoff(retries: u32, info: Option<&RateLimitInfo>) -> Duration {
+    info.and_then(|info| info.retry_after.or(info.reset))
+        .unwrap_or_else(|| Duration::from_millis(100) * 2_u32.pow(retries))
 }
 
 pub mod types {
+    use std::borrow::Cow;
+    use std::collections::{BTreeMap, HashMap, HashSet};
+    use std::convert::Infallible;
     use std::fmt;
-    use std::{collections::HashMap, ops::Deref};
+    use std::fs::{self, File};
+    use std::io::Read;
+    use std::ops::Deref;
+    use std::path::{Path, PathBuf};
+    use std::str::FromStr;
 
+    use base64::{engine::general_purpose::STANDARD, Engine};
     use ecow::EcoString;
-    use serde::{Deserialize, Serialize};
+    use reqwest::header::HeaderMap;
+    use serde::ser;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::{MAX_ATTACHMENT_BYTES, MAX_RECIPIENTS};
+    use crate::types::ValidationError;
 
     /// Unique [`Email`] identifier.
-    #[derive(Debug, Clone, Deserialize)]
+    #[derive(Debug, Clone, Serialize, Deserialize)]
     pub struct EmailId(EcoString);
 
     impl EmailId {
@@ -76,25 +497,203 @@ pub mod types {
         }
     }
 
+    impl FromStr for EmailId {
+        type Err = Infallible;
+
+        /// Same as [`EmailId::new`], never fails.
+        #[inline]
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            Ok(Self::new(s))
+        }
+    }
+
     impl fmt::Display for EmailId {
         fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
             fmt::Display::fmt(self.as_ref(), f)
         }
     }
 
+    /// Email address with an optional display name, e.g. `Acme <onboarding@resend.dev>`.
+    ///
+    /// Formats the display name as an RFC 5322 quoted string when needed, so names
+    /// containing special characters like commas are handled correctly.
+    ///
+    /// Converts into a [`String`] and can be used anywhere an address is expected:
+    ///
+    /// ```
+    /// use resend_rs::types::{CreateEmailBaseOptions, EmailAddress};
+    ///
+    /// let from = EmailAddress::new("john@acme.dev").with_name("Doe, John");
+    /// let email = CreateEmailBaseOptions::new(from, ["delivered@resend.dev"], "Hello World!");
+    ///
+    /// assert_eq!(email.from, r#""Doe, John" <john@acme.dev>"#);
+    /// ```
+    #[must_use]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct EmailAddress {
+        /// Display name shown to the recipient.
+        pub name: Option<String>,
+        /// The address itself, e.g. `onboarding@resend.dev`.
+        pub address: String,
+    }
+
+    impl EmailAddress {
+        /// Creates a new [`EmailAddress`] without a display name.
+        #[inline]
+        pub fn new(address: &str) -> Self {
+            Self {
+                name: None,
+                address: address.to_owned(),
+            }
+        }
+
+        /// Adds or overwrites the display name.
+        #[inline]
+        pub fn with_name(mut self, name: &str) -> Self {
+            self.name = Some(name.to_owned());
+            self
+        }
+    }
+
+    impl fmt::Display for EmailAddress {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            let Some(name) = self.name.as_deref().filter(|name| !name.is_empty()) else {
+                return f.write_str(&self.address);
+            };
+
+            // A phrase made of atoms can be written as is, anything else has to be quoted.
+            let is_atext =
+                |c: char| c.is_alphanumeric() || !c.is_ascii() || "!#$%&'*+-/=?^_`{|}~".contains(c);
+            let is_phrase = name
+                .split(' ')
+                .all(|word| !word.is_empty() && word.chars().all(is_atext));
+
+            if is_phrase {
+                return write!(f, "{name} <{}>", self.address);
+            }
+
+            f.write_str("\"")?;
+            for c in name.chars() {
+                if c == '"' || c == '\\' {
+                    f.write_str("\\")?;
+                }
+                write!(f, "{c}")?;
+            }
+            write!(f, "\" <{}>", self.address)
+        }
+    }
+
+    impl FromStr for EmailAddress {
+        type Err = ValidationError;
+
+        /// Parses `address`, `Name <address>` or `"Quoted, Name" <address>`, the formats written
+        /// by [`EmailAddress::to_string`].
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            let invalid = || ValidationError::InvalidAddress(s.to_owned());
+            let trimmed = s.trim();
+
+            let (name, address) = match trimmed.strip_suffix('>') {
+                Some(rest) => {
+                    // The address can't contain `<`, unlike a quoted name.
+                    let (name, address) = rest.rsplit_once('<').ok_or_else(invalid)?;
+                    (Some(name.trim_end_matches(' ')), address)
+                }
+                None => (None, trimmed),
+            };
+
+            let valid = address.split_once('@').is_some_and(|(local, domain)| {
+                !local.is_empty() && !domain.is_empty() && !domain.contains('@')
+            });
+            if !valid || address.contains(|c: char| c.is_whitespace() || "<>\"".contains(c)) {
+                return Err(invalid());
+            }
+
+            let name = name.filter(|name| !name.is_empty()).map(|name| {
+                let Some(quoted) = name
+                    .strip_prefix('"')
+                    .and_then(|name| name.strip_suffix('"'))
+                else {
+                    return name.to_owned();
+                };
+
+                let mut unquoted = String::with_capacity(quoted.len());
+                let mut chars = quoted.chars();
+                while let Some(c) = chars.next() {
+                    match c {
+                        '\\' => unquoted.extend(chars.next()),
+                        c => unquoted.push(c),
+                    }
+                }
+                unquoted
+            });
+
+            Ok(Self {
+                name,
+                address: address.to_owned(),
+            })
+        }
+    }
+
+    #[cfg(feature = "rfc2047")]
+    impl EmailAddress {
+        /// Same as [`EmailAddress::to_string`], but encodes a display name containing non-ASCII
+        /// characters as RFC 2047 encoded words (`=?UTF-8?B?...?=`). The address is left as is.
+        ///
+        /// Some mail paths mangle raw UTF-8 headers, encoded words survive all of them.
+        ///
+        /// ```
+        /// use resend_rs::types::EmailAddress;
+        ///
+        /// let from = EmailAddress::new("team@acme.dev").with_name("Café Acme");
+        /// assert_eq!(from.to_rfc2047_string(), "=?UTF-8?B?Q2Fmw6kgQWNtZQ==?= <team@acme.dev>");
+        /// ```
+        #[must_use]
+        pub fn to_rfc2047_string(&self) -> String {
+            // Encoded words can't be longer than 75 characters: 12 for the delimiters, leaving
+            // 60 base64 characters for (at most) 45 bytes of the name.
+            const MAX_CHUNK: usize = 45;
+
+            let Some(name) = self.name.as_deref().filter(|name| !name.is_ascii()) else {
+                return self.to_string();
+            };
+
+            let mut words = Vec::new();
+            let mut chunk = String::new();
+            for c in name.chars() {
+                if chunk.len() + c.len_utf8() > MAX_CHUNK {
+                    words.push(format!("=?UTF-8?B?{}?=", STANDARD.encode(&chunk)));
+                    chunk.clear();
+                }
+                chunk.push(c);
+            }
+            words.push(format!("=?UTF-8?B?{}?=", STANDARD.encode(&chunk)));
+
+            format!("{} <{}>", words.join(" "), self.address)
+        }
+    }
+
+    impl From<EmailAddress> for String {
+        #[inline]
+        fn from(value: EmailAddress) -> Self {
+            value.to_string()
+        }
+    }
+
     /// All requisite components and associated data to send an email.
     ///
     /// See [`docs`].
     ///
     /// [`docs`]: https://resend.com/docs/api-reference/emails/send-email#body-parameters
     #[must_use]
-    #[derive(Debug, Clone, Serialize)]
+    #[derive(Debug, Default, Clone, Serialize)]
     pub struct CreateEmailBaseOptions {
         /// Sender email address.
         ///
         /// To include a friendly name, use the format:
         ///
         /// `Your Name <sender@domain.com>`
+        ///
+        /// or build it from an [`EmailAddress`], which takes care of quoting the name.
         pub from: String,
         /// Recipient email address. Max 50.
         pub to: Vec<String>,
@@ -118,7 +717,10 @@ pub mod types {
         #[serde(skip_serializing_if = "Option::is_none")]
         pub reply_to: Option<Vec<String>>,
         /// Custom headers to add to the email.
-        #[serde(skip_serializing_if = "Option::is_none")]
+        #[serde(
+            skip_serializing_if = "Option::is_none",
+            serialize_with = "serialize_sorted"
+        )]
         pub headers: Option<HashMap<String, String>>,
         /// Filename and content of attachments (max 40mb per email).
         #[serde(skip_serializing_if = "Option::is_none")]
@@ -126,6 +728,12 @@ pub mod types {
         /// Email tags.
         #[serde(skip_serializing_if = "Option::is_none")]
         pub tags: Option<Vec<Tag>>,
+        /// Schedule email to be sent later.
+        ///
+        /// The date should be in ISO 8601 format (e.g. `2024-08-05T11:52:01.858Z`) or in natural
+        /// language (e.g. `in 1 hour`).
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub scheduled_at: Option<String>,
     }
 
     impl CreateEmailBaseOptions {
@@ -150,9 +758,45 @@ pub mod types {
                 headers: None,
                 attachments: None,
                 tags: None,
+                scheduled_at: None,
             }
         }
 
+        /// Creates an empty [`CreateEmailBaseOptions`], to be filled field by field.
+        ///
+        /// The result is only valid once `from`, `to` and `subject` are populated, e.g. with
+        /// [`with_from`], [`with_to`] and [`with_subject`]. Sending it before that fails with a
+        /// [`ValidationError`] without making a request.
+        ///
+        /// [`with_from`]: Self::with_from
+        /// [`with_to`]: Self::with_to
+        /// [`with_subject`]: Self::with_subject
+        #[inline]
+        pub fn empty() -> Self {
+            Self::default()
+        }
+
+        /// Sets or overwrites the sender email address.
+        #[inline]
+        pub fn with_from(mut self, from: &str) -> Self {
+            from.clone_into(&mut self.from);
+            self
+        }
+
+        /// Adds another recipient email address.
+        #[inline]
+        pub fn with_to(mut self, address: &str) -> Self {
+            self.to.push(address.to_owned());
+            self
+        }
+
+        /// Sets or overwrites the email subject.
+        #[inline]
+        pub fn with_subject(mut self, subject: &str) -> Self {
+            subject.clone_into(&mut self.subject);
+            self
+        }
+
         /// Adds or overwrites the HTML version of the message.
         #[inline]
         pub fn with_html(mut self, html: &str) -> Self {
@@ -160,13 +804,66 @@ pub mod types {
             self
         }
 
+        /// Adds or overwrites the HTML version of the message, and attaches the `images` it
+        /// references as `cid:name`.
+        ///
+        /// Each referenced image becomes an inline attachment with `name` as its content id and
+        /// filename. Images that aren't referenced are left out.
+        pub fn with_html_and_images(
+            mut self,
+            html: &str,
+            mut images: HashMap<String, Vec<u8>>,
+        ) -> Self {
+            let ends_reference = |c: char| matches!(c, '"' | '\'' | ')' | '>') || c.is_whitespace();
+
+            for (start, _) in html.match_indices("cid:") {
+                let name = &html[start + "cid:".len()..];
+                let name = name.find(ends_reference).map_or(name, |end| &name[..end]);
+
+                if let Some(content) = images.remove(name) {
+                    let image = Attachment::from_content(content)
+                        .with_filename(name)
+                        .with_content_id(name)
+                        .with_disposition(Disposition::Inline);
+                    self = self.with_attachment(image);
+                }
+            }
+
+            self.with_html(html)
+        }
+
+        /// Derives the plain text version of the message from its HTML version, improving
+        /// deliverability with clients that prefer text.
+        ///
+        /// Call it after [`CreateEmailBaseOptions::with_html`], does nothing without HTML or if a
+        /// plain text version is already set. Links are kept as numbered references.
+        #[cfg(feature = "html2text")]
+        pub fn with_auto_text(mut self) -> Self {
+            if let (Some(html), None) = (&self.html, &self.text) {
+                self.text = Some(html2text::from_read(html.as_bytes(), 80));
+            }
+            self
+        }
+
         /// Adds or overwrites the plain text version of the message.
+        ///
+        /// Set it along with the HTML version when possible: the API then sends a
+        /// `multipart/alternative` message with the text part first, so clients that render HTML
+        /// pick it while others fall back to the text, and spam filters tend to score HTML-only
+        /// emails lower.
         #[inline]
         pub fn with_text(mut self, text: &str) -> Self {
             self.text = Some(text.to_owned());
             self
         }
 
+        /// Adds or overwrites both the plain text and the HTML versions of the message, see
+        /// [`CreateEmailBaseOptions::with_text`].
+        #[inline]
+        pub fn with_text_and_html(self, text: &str, html: &str) -> Self {
+            self.with_text(text).with_html(html)
+        }
+
         /// Attaches `bcc` recipient email address.
         pub fn with_bcc(mut self, address: &str) -> Self {
             let bcc = self.bcc.get_or_insert_with(Vec::new);
@@ -188,6 +885,23 @@ pub mod types {
             self
         }
 
+        /// Sets the sender from an [`EmailAddress`] whose display name is RFC 2047 encoded if it
+        /// contains non-ASCII characters, see [`EmailAddress::to_rfc2047_string`].
+        #[inline]
+        #[cfg(feature = "rfc2047")]
+        pub fn with_encoded_from(mut self, from: &EmailAddress) -> Self {
+            self.from = from.to_rfc2047_string();
+            self
+        }
+
+        /// Same as [`CreateEmailBaseOptions::with_reply`], but RFC 2047 encodes the display name
+        /// if it contains non-ASCII characters, see [`EmailAddress::to_rfc2047_string`].
+        #[inline]
+        #[cfg(feature = "rfc2047")]
+        pub fn with_encoded_reply(self, to: &EmailAddress) -> Self {
+            self.with_reply(&to.to_rfc2047_string())
+        }
+
         /// Adds or overwrites an email header.
         pub fn with_header(mut self, name: &str, value: &str) -> Self {
             let headers = self.headers.get_or_insert_with(HashMap::new);
@@ -196,6 +910,21 @@ pub mod types {
             self
         }
 
+        /// Adds or overwrites email headers from a [`HeaderMap`].
+        ///
+        /// Headers whose value isn't valid UTF-8 are skipped. If a header has several values, the
+        /// last one is used.
+        pub fn with_headers_from(mut self, map: &HeaderMap) -> Self {
+            let headers = self.headers.get_or_insert_with(HashMap::new);
+            for (name, value) in map {
+                if let Ok(value) = value.to_str() {
+                    let _ = headers.insert(name.as_str().to_owned(), value.to_owned());
+                }
+            }
+
+            self
+        }
+
         /// Adds another attachment.
         ///
         /// Limited to max 40mb per email.
@@ -205,18 +934,194 @@ pub mod types {
             self
         }
 
+        /// Adds several attachments at once.
+        ///
+        /// Limited to max 40mb per email.
+        pub fn with_attachments<A>(mut self, files: impl IntoIterator<Item = A>) -> Self
+        where
+            A: Into<Attachment>,
+        {
+            let attachments = self.attachments.get_or_insert_with(Vec::new);
+            attachments.extend(files.into_iter().map(Into::into));
+            self
+        }
+
         /// Adds additional email tag.
         pub fn with_tag(mut self, tag: impl Into<Tag>) -> Self {
             let tags = self.tags.get_or_insert_with(Vec::new);
             tags.push(tag.into());
             self
         }
-    }
+
+        /// Adds a tag for every entry of `tags`, in order of their names.
+        ///
+        /// ### Errors
+        ///
+        /// - [`ValidationError::InvalidTag`] if a name or value isn't valid, see [`Tag::validate`].
+        pub fn with_tags_from_map(
+            mut self,
+            tags: HashMap<String, String>,
+        ) -> Result<Self, ValidationError> {
+            let mut tags: Vec<_> = tags
+                .into_iter()
+                .map(|(name, value)| Tag { name, value })
+                .collect();
+            tags.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+
+            for tag in tags {
+                tag.validate()?;
+                self = self.with_tag(tag);
+            }
+
+            Ok(self)
+        }
+
+        /// Schedules the email to be sent later.
+        ///
+        /// The date should be in ISO 8601 format (e.g. `2024-08-05T11:52:01.858Z`) or in natural
+        /// language (e.g. `in 1 hour`).
+        #[inline]
+        pub fn with_scheduled_at(mut self, scheduled_at: &str) -> Self {
+            self.scheduled_at = Some(scheduled_at.to_owned());
+            self
+        }
+
+        /// Removes the `cc` and `bcc` addresses that are already in `to`, so they aren't sent
+        /// the email twice. Addresses are compared case-insensitively.
+        ///
+        /// See [`CreateEmailBaseOptions::validate_unique_recipients`] to reject these instead.
+        pub fn with_deduplicated_recipients(mut self) -> Self {
+            let to = &self.to;
+            for list in [&mut self.cc, &mut self.bcc].into_iter().flatten() {
+                list.retain(|address| !to.iter().any(|to| to.eq_ignore_ascii_case(address)));
+            }
+
+            self
+        }
+
+        /// Checks that no `cc` or `bcc` address is already in `to`, compared case-insensitively.
+        ///
+        /// Unlike [`CreateEmailBaseOptions::validate`], this isn't done automatically before
+        /// sending.
+        ///
+        /// ### Errors
+        ///
+        /// - [`ValidationError::DuplicateRecipient`] with the first address found twice.
+        pub fn validate_unique_recipients(&self) -> Result<(), ValidationError> {
+            let mut copies = [&self.cc, &self.bcc].into_iter().flatten().flatten();
+            copies
+                .find(|address| self.to.iter().any(|to| to.eq_ignore_ascii_case(address)))
+                .map_or(Ok(()), |address| {
+                    Err(ValidationError::DuplicateRecipient(address.clone()))
+                })
+        }
+
+        /// Returns the JSON body this email is sent as, without needing a client.
+        ///
+        /// Doesn't include the default tags of a client, see [`ResendBuilder::default_tags`].
+        ///
+        /// ### Errors
+        ///
+        /// - Fails if the email can't be serialized, which doesn't happen in practice.
+        ///
+        /// [`ResendBuilder::default_tags`]: crate::ResendBuilder::default_tags
+        pub fn to_request_json(&self) -> serde_json::Result<serde_json::Value> {
+            serde_json::to_value(self)
+        }
+
+        /// Adds the `defaults` whose name isn't used by any of the email's tags.
+        ///
+        /// Only clones the email if there is a tag to add.
+        pub(crate) fn with_default_tags(&self, defaults: &[Tag]) -> Cow<'_, Self> {
+            let tags = self.tags.as_deref().unwrap_or_default();
+            let missing = defaults
+                .iter()
+                .filter(|default| tags.iter().all(|tag| tag.name != default.name))
+                .cloned()
+                .collect::<Vec<_>>();
+
+            if missing.is_empty() {
+                return Cow::Borrowed(self);
+            }
+
+            let mut email = self.clone();
+            email.tags.get_or_insert_with(Vec::new).extend(missing);
+            Cow::Owned(email)
+        }
+
+        /// Checks that the email can be sent, this is done automatically before sending.
+        pub fn validate(&self) -> Result<(), ValidationError> {
+            if self.from.is_empty() {
+                return Err(ValidationError::MissingField("from"));
+            }
+            if self.to.iter().all(|address| address.trim().is_empty()) {
+                return Err(ValidationError::NoRecipients);
+            }
+            if self.subject.is_empty() {
+                return Err(ValidationError::MissingField("subject"));
+            }
+
+            let count = self.to.len();
+            if count > MAX_RECIPIENTS {
+                let max = MAX_RECIPIENTS;
+                return Err(ValidationError::TooManyRecipients { count, max });
+            }
+
+            let attachments = self.attachments.as_deref().unwrap_or_default();
+            let size = attachments
+                .iter()
+                .map(|attachment| match &attachment.content_or_path {
+                    ContentOrPath::Content(content) => content.len(),
+                    // Unreadable files fail once sent instead.
+                    ContentOrPath::File(path) => fs::metadata(path).map_or(0, |metadata| {
+                        usize::try_from(metadata.len()).unwrap_or(usize::MAX)
+                    }),
+                    ContentOrPath::Path(_) => 0,
+                })
+                .sum::<usize>();
+            if size > MAX_ATTACHMENT_BYTES {
+                let max = MAX_ATTACHMENT_BYTES;
+                return Err(ValidationError::AttachmentsTooLarge { size, max });
+            }
+
+            let mut names = HashSet::new();
+            let tags = self.tags.as_deref().unwrap_or_default();
+            if let Some(tag) = tags.iter().find(|tag| !names.insert(tag.name.as_str())) {
+                return Err(ValidationError::DuplicateTag(tag.name.clone()));
+            }
+
+            Ok(())
+        }
+    }
+
+    impl From<(&str, &str, &str)> for CreateEmailBaseOptions {
+        /// Creates an email from a `(from, to, subject)` tuple.
+        ///
+        /// A body still has to be added, e.g. with [`CreateEmailBaseOptions::with_text`].
+        #[inline]
+        fn from((from, to, subject): (&str, &str, &str)) -> Self {
+            Self::new(from, [to], subject)
+        }
+    }
+
+    #[derive(Debug, Clone, Serialize)]
+    pub struct UpdateEmailOptions {
+        /// The new date and time the email is scheduled for.
+        pub scheduled_at: String,
+    }
 
     #[derive(Debug, Clone, Deserialize)]
     pub struct CreateEmailResponse {
         /// The ID of the sent email.
         pub id: EmailId,
+        /// Non-fatal warnings about the request, e.g. about deprecated fields.
+        #[serde(default)]
+        pub warnings: Vec<String>,
+        /// When the email is scheduled for, as normalized by the API (e.g. from `in 1 hour`).
+        ///
+        /// `None` for emails sent right away, or if the API didn't echo it.
+        #[serde(default)]
+        pub scheduled_at: Option<String>,
     }
 
     #[derive(Debug, Clone, Deserialize)]
@@ -225,6 +1130,51 @@ pub mod types {
         pub data: Vec<CreateEmailResponse>,
     }
 
+    /// Event in the lifecycle of a sent [`Email`], as reported by [`Email::last_event`].
+    ///
+    /// <https://resend.com/docs/dashboard/webhooks/event-types>
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+    #[serde(rename_all = "snake_case")]
+    pub enum EmailEvent {
+        Bounced,
+        Canceled,
+        Clicked,
+        Complained,
+        Delivered,
+        DeliveryDelayed,
+        Failed,
+        Opened,
+        Queued,
+        Scheduled,
+        Sent,
+    }
+
+    impl EmailEvent {
+        /// Returns the name of the event, as sent by the API.
+        #[must_use]
+        pub const fn as_str(self) -> &'static str {
+            match self {
+                Self::Bounced => "bounced",
+                Self::Canceled => "canceled",
+                Self::Clicked => "clicked",
+                Self::Complained => "complained",
+                Self::Delivered => "delivered",
+                Self::DeliveryDelayed => "delivery_delayed",
+                Self::Failed => "failed",
+                Self::Opened => "opened",
+                Self::Queued => "queued",
+                Self::Scheduled => "scheduled",
+                Self::Sent => "sent",
+            }
+        }
+    }
+
+    impl fmt::Display for EmailEvent {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str(self.as_str())
+        }
+    }
+
     /// Name and value of the attached [`Email`] tag.
     #[must_use]
     #[derive(Debug, Clone, Serialize)]
@@ -249,6 +1199,27 @@ pub mod types {
                 value: value.to_owned(),
             }
         }
+
+        /// Checks that the name and value only contain allowed characters and are at most 256
+        /// characters long.
+        ///
+        /// ### Errors
+        ///
+        /// - [`ValidationError::InvalidTag`] with the name of the tag.
+        pub fn validate(&self) -> Result<(), ValidationError> {
+            let valid = |s: &str| {
+                !s.is_empty()
+                    && s.len() <= 256
+                    && s.bytes()
+                        .all(|b| b.is_ascii_alphanumeric() || b == b'_' || b == b'-')
+            };
+
+            if valid(&self.name) && valid(&self.value) {
+                Ok(())
+            } else {
+                Err(ValidationError::InvalidTag(self.name.clone()))
+            }
+        }
     }
 
     /// Filename and content of the [`CreateEmailBaseOptions`] attachment.
@@ -265,30 +1236,131 @@ pub mod types {
         pub filename: Option<String>,
         /// Optional content type for the attachment, if not set will be derived from the filename
         /// property.
-        #[serde(rename = "contentType", skip_serializing_if = "Option::is_none")]
+        #[serde(skip_serializing_if = "Option::is_none")]
         pub content_type: Option<String>,
+        /// Whether the file is displayed inline or offered as a download.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub disposition: Option<Disposition>,
+        /// Id the HTML body references the file by, as in `<img src="cid:logo">`.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub content_id: Option<String>,
+    }
+
+    /// How the [`Attachment`] is presented by email clients.
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize)]
+    #[serde(rename_all = "lowercase")]
+    pub enum Disposition {
+        /// Displayed within the body of the email, e.g. an embedded image.
+        Inline,
+        /// Offered as a downloadable file.
+        Attachment,
     }
 
     /// Content or path of the [`Attachment`].
     #[must_use]
     #[derive(Debug, Clone, Serialize)]
     pub enum ContentOrPath {
-        /// Content of an attached file.
-        #[serde(rename = "content")]
+        /// Content of an attached file, sent base64 encoded.
+        #[serde(rename = "content", serialize_with = "serialize_base64")]
         Content(Vec<u8>),
         /// Path where the attachment file is hosted.
         #[serde(rename = "path")]
         Path(String),
+        /// Local file read while the request is serialized, see [`Attachment::from_file`].
+        #[serde(rename = "content", serialize_with = "serialize_file")]
+        File(PathBuf),
+    }
+
+    /// Serializes `headers` sorted by name, so that the same email always serializes the same way.
+    #[allow(clippy::ref_option)]
+    fn serialize_sorted<S: Serializer>(
+        headers: &Option<HashMap<String, String>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let sorted = headers
+            .as_ref()
+            .map(|headers| headers.iter().collect::<BTreeMap<_, _>>());
+        sorted.serialize(serializer)
+    }
+
+    /// Serializes `content` as a base64 string, a third larger than the content instead of the
+    /// three to four times of a JSON array of numbers.
+    fn serialize_base64<S: Serializer>(content: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&STANDARD.encode(content))
+    }
+
+    /// Size of the chunks `serialize_file` reads, a multiple of 3 bytes so that the chunks
+    /// encode without padding in between.
+    pub const FILE_CHUNK: u64 = 3 * 1024 * 8;
+
+    /// Serializes the content of the file at `path` like [`ContentOrPath::Content`], reading and
+    /// encoding it in chunks so that only the encoded content is held in memory, not also the raw
+    /// file.
+    fn serialize_file<S: Serializer>(path: &Path, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut file = File::open(path).map_err(ser::Error::custom)?;
+        let size = file.metadata().map_or(0, |metadata| metadata.len());
+        let capacity = usize::try_from(size.div_ceil(3) * 4).unwrap_or_default();
+        let mut encoded = String::with_capacity(capacity);
+        let mut chunk = Vec::new();
+
+        loop {
+            chunk.clear();
+            let read = (&mut file)
+                .take(FILE_CHUNK)
+                .read_to_end(&mut chunk)
+                .map_err(ser::Error::custom)?;
+            if read == 0 {
+                break;
+            }
+            STANDARD.encode_string(&chunk, &mut encoded);
+        }
+
+        serializer.serialize_str(&encoded)
     }
 
     impl Attachment {
         /// Creates a new [`Attachment`] from the content of an attached file.
         #[inline]
-        pub fn from_content(content: Vec<u8>) -> Self {
+        pub const fn from_content(content: Vec<u8>) -> Self {
             Self {
                 content_or_path: ContentOrPath::Content(content),
                 filename: None,
                 content_type: None,
+                disposition: None,
+                content_id: None,
+            }
+        }
+
+        /// Creates a new [`Attachment`] from the parts of an uploaded file, e.g. a multipart form
+        /// field.
+        #[inline]
+        pub fn from_parts(
+            filename: Option<&str>,
+            content_type: Option<&str>,
+            content: Vec<u8>,
+        ) -> Self {
+            Self {
+                filename: filename.map(ToOwned::to_owned),
+                content_type: content_type.map(ToOwned::to_owned),
+                ..Self::from_content(content)
+            }
+        }
+
+        /// Creates a new [`Attachment`] from a local file.
+        ///
+        /// The file is only read when the email is sent, not when it is built, and reading
+        /// fails when sending instead. The request body still holds the whole file, base64
+        /// encoded, and its size counts towards [`MAX_ATTACHMENT_BYTES`].
+        ///
+        /// [`MAX_ATTACHMENT_BYTES`]: crate::limits::MAX_ATTACHMENT_BYTES
+        #[inline]
+        pub fn from_file(path: impl Into<PathBuf>) -> Self {
+            Self {
+                content_or_path: ContentOrPath::File(path.into()),
+                filename: None,
+                content_type: None,
+                disposition: None,
+                content_id: None,
             }
         }
 
@@ -299,6 +1371,8 @@ pub mod types {
                 content_or_path: ContentOrPath::Path(path.to_owned()),
                 filename: None,
                 content_type: None,
+                disposition: None,
+                content_id: None,
             }
         }
 
@@ -315,6 +1389,21 @@ pub mod types {
             self.content_type = Some(content_type.to_owned());
             self
         }
+
+        /// Sets the id the HTML body references the attached file by, see
+        /// [`Attachment::content_id`].
+        #[inline]
+        pub fn with_content_id(mut self, content_id: &str) -> Self {
+            self.content_id = Some(content_id.to_owned());
+            self
+        }
+
+        /// Sets whether the attached file is displayed inline or offered as a download.
+        #[inline]
+        pub const fn with_disposition(mut self, disposition: Disposition) -> Self {
+            self.disposition = Some(disposition);
+            self
+        }
     }
 
     impl From<Vec<u8>> for Attachment {
@@ -333,7 +1422,7 @@ pub mod types {
 
     /// Received email.
     #[must_use]
-    #[derive(Debug, Clone, Deserialize)]
+    #[derive(Debug, Clone, Serialize, Deserialize)]
     pub struct Email {
         /// The ID of the email.
         pub id: EmailId,
@@ -341,6 +1430,7 @@ pub mod types {
         /// Sender email address.
         pub from: String,
         /// Recipient email address.
+        #[serde(default)]
         pub to: Vec<String>,
         /// The subject line of the email.
         pub subject: String,
@@ -349,24 +1439,1399 @@ pub mod types {
         pub created_at: String,
         /// The HTML body of the email.
         pub html: Option<String>,
-        /// The plain text body of the email.
-        pub text: String,
+        /// The plain text body of the email, `None` for HTML-only emails.
+        pub text: Option<String>,
 
-        /// The email addresses of the blind carbon copy recipients.
+        /// The email addresses of the blind carbon copy recipients, empty if omitted.
+        #[serde(default)]
         pub bcc: Vec<String>,
-        /// The email addresses of the carbon copy recipients.
+        /// The email addresses of the carbon copy recipients, empty if omitted.
+        #[serde(default)]
         pub cc: Vec<String>,
         /// The email addresses to which replies should be sent.
+        ///
+        /// A single address sent as a string is normalized to a one-element list.
+        #[serde(default, deserialize_with = "string_or_array")]
         pub reply_to: Option<Vec<String>>,
         /// The status of the email.
         pub last_event: String,
     }
+
+    /// Deserializes either a string or an array of strings, or `null`.
+    fn string_or_array<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Vec<String>>, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Addresses {
+            One(String),
+            Many(Vec<String>),
+        }
+
+        Ok(
+            Option::<Addresses>::deserialize(deserializer)?.map(|addresses| match addresses {
+                Addresses::One(address) => vec![address],
+                Addresses::Many(addresses) => addresses,
+            }),
+        )
+    }
+
+    impl Email {
+        /// Serializes the email to JSON, in the same format the API returns it.
+        ///
+        /// ### Errors
+        ///
+        /// - Fails if the email can't be serialized, which doesn't happen in practice.
+        pub fn to_json(&self) -> serde_json::Result<String> {
+            serde_json::to_string(self)
+        }
+
+        /// Parses an email from JSON, as returned by the API or [`Email::to_json`].
+        ///
+        /// ### Errors
+        ///
+        /// - Fails if `json` is not a valid email.
+        pub fn from_json(json: &str) -> serde_json::Result<Self> {
+            serde_json::from_str(json)
+        }
+    }
 }
 
 #[cfg(test)]
 mod test {
-    use crate::types::{CreateEmailBaseOptions, Tag};
-    use crate::{tests::CLIENT, Resend, Result};
+    use super::idempotency_key;
+    use crate::services::SendEmails;
+    use crate::tests::mock::{MockResponse, MockServer};
+    #[cfg(not(feature = "blocking"))]
+    use crate::types::EmailEvent;
+    use crate::types::{
+        Attachment, ContentOrPath, CreateEmailBaseOptions, CreateEmailResponse, Disposition, Email,
+        EmailAddress, EmailId, ErrorResponse, Tag, ValidationError,
+    };
+    use crate::{tests::CLIENT, Error, Resend, Result};
+
+    /// Fake [`SendEmails`] implementation that hands out sequential ids.
+    struct FakeEmails;
+
+    #[maybe_async::maybe_async]
+    impl SendEmails for FakeEmails {
+        async fn send(&self, _email: CreateEmailBaseOptions) -> Result<CreateEmailResponse> {
+            Ok(CreateEmailResponse {
+                id: EmailId::new("fake"),
+                warnings: Vec::new(),
+                scheduled_at: None,
+            })
+        }
+
+        async fn send_batch(
+            &self,
+            emails: Vec<CreateEmailBaseOptions>,
+        ) -> Result<Vec<CreateEmailResponse>> {
+            let ids = (0..emails.len()).map(|i| CreateEmailResponse {
+                id: EmailId::new(&format!("fake-{i}")),
+                warnings: Vec::new(),
+                scheduled_at: None,
+            });
+
+            Ok(ids.collect())
+        }
+
+        async fn get(&self, _email_id: &str) -> Result<Email> {
+            Err(Error::Resend(ErrorResponse {
+                status_code: 404,
+                message: "Email not found".to_owned(),
+                name: "not_found".to_owned(),
+                request_id: None,
+                rate_limit: None,
+            }))
+        }
+    }
+
+    #[tokio::test]
+    #[cfg(not(feature = "blocking"))]
+    async fn empty_then_setters() -> Result<()> {
+        let server = MockServer::start(|_| MockResponse::json(200, r#"{"id":"1"}"#));
+        let resend = Resend::builder("re_123").base_url(server.url()).build()?;
+
+        // Not fully populated, rejected before the request is made.
+        let email = CreateEmailBaseOptions::empty().with_from("from@a.dev");
+        let result = resend.emails.send(email.clone()).await;
+        assert!(matches!(
+            result,
+            Err(Error::Validation(ValidationError::NoRecipients))
+        ));
+        assert!(server.requests().is_empty());
+
+        let email = email
+            .with_to("to@a.dev")
+            .with_subject("Subject")
+            .with_text("Hello World!");
+        let _ = resend.emails.send(email).await?;
+        assert_eq!(server.requests().len(), 1);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[cfg(not(feature = "blocking"))]
+    async fn no_recipients() -> Result<()> {
+        let server = MockServer::start(|_| MockResponse::json(200, r#"{"id":"1"}"#));
+        let resend = Resend::builder("re_123").base_url(server.url()).build()?;
+
+        for to in [vec![], vec![String::new()], vec![" ".to_owned()]] {
+            let email = CreateEmailBaseOptions::new("from@a.dev", to, "Subject");
+            let result = resend.emails.send(email).await;
+            assert!(matches!(
+                result,
+                Err(Error::Validation(ValidationError::NoRecipients))
+            ));
+        }
+        assert!(server.requests().is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[cfg(not(feature = "blocking"))]
+    async fn duplicate_tags() -> Result<()> {
+        let server = MockServer::start(|_| MockResponse::json(200, r#"{"id":"1"}"#));
+        let resend = Resend::builder("re_123").base_url(server.url()).build()?;
+
+        let email = CreateEmailBaseOptions::new("from@a.dev", ["to@a.dev"], "Subject")
+            .with_tag(Tag::new("category", "welcome"))
+            .with_tag(Tag::new("region", "eu"))
+            .with_tag(Tag::new("category", "onboarding"));
+        let result = resend.emails.send(email).await;
+
+        assert!(matches!(
+            result,
+            Err(Error::Validation(ValidationError::DuplicateTag(name))) if name == "category"
+        ));
+        assert!(server.requests().is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[cfg(not(feature = "blocking"))]
+    async fn validates_before_rate_limiting() -> Result<()> {
+        use std::time::{Duration, Instant};
+
+        let server = MockServer::start(|_| MockResponse::json(200, r#"{"id":"1"}"#));
+        let resend = Resend::builder("re_123").base_url(server.url()).build()?;
+        let email = CreateEmailBaseOptions::new("from@a.dev", ["to@a.dev"], "Subject");
+
+        // Use up the whole burst.
+        for _ in 0..8 {
+            let _ = resend.emails.send(email.clone()).await?;
+        }
+
+        let start = Instant::now();
+        let result = resend.emails.send(email.clone().with_subject("")).await;
+        assert!(matches!(result, Err(Error::Validation(_))));
+        assert!(start.elapsed() < Duration::from_millis(50));
+
+        // Whereas a valid email has to wait for the limiter.
+        let start = Instant::now();
+        let _ = resend.emails.send(email).await?;
+        assert!(start.elapsed() >= Duration::from_millis(50));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[cfg(not(feature = "blocking"))]
+    async fn send_priority() -> Result<()> {
+        use std::time::{Duration, Instant};
+
+        let server = MockServer::start(|_| MockResponse::json(200, r#"{"id":"1"}"#));
+        let resend = Resend::builder("re_123").base_url(server.url()).build()?;
+
+        // Saturate the regular lane.
+        let limiter = resend.rate_limiter();
+        for _ in 0..8 {
+            limiter.acquire().await;
+        }
+
+        let start = Instant::now();
+        let email = CreateEmailBaseOptions::new("from@a.dev", ["to@a.dev"], "Password reset");
+        let _ = resend.emails.send_priority(&email).await?;
+        assert!(start.elapsed() < Duration::from_millis(100));
+        assert_eq!(server.requests().len(), 1);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[cfg(not(feature = "blocking"))]
+    async fn send_ref_twice() -> Result<()> {
+        let server = MockServer::start(|_| MockResponse::json(200, r#"{"id":"1"}"#));
+        let resend = Resend::builder("re_123").base_url(server.url()).build()?;
+
+        let template = CreateEmailBaseOptions::new("from@a.dev", ["to@a.dev"], "Subject")
+            .with_text("Hello World!");
+        let _ = resend.emails.send_ref(&template).await?;
+        let _ = resend.emails.send_ref(&template).await?;
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].body, requests[1].body);
+
+        Ok(())
+    }
+
+    #[maybe_async::test(feature = "blocking", async(not(feature = "blocking"), tokio::test))]
+    async fn send_many() -> Result<()> {
+        use std::time::{Duration, Instant};
+
+        // Echo the subject back as the id to check the results order.
+        let server = MockServer::start(|request| {
+            let email = serde_json::from_slice::<serde_json::Value>(&request.body);
+            let subject = email.unwrap_or_default()["subject"].clone();
+            MockResponse::json(200, &serde_json::json!({ "id": subject }).to_string())
+        });
+        let resend = Resend::builder("re_123").base_url(server.url()).build()?;
+
+        let emails = (0..8)
+            .map(|i| CreateEmailBaseOptions::new("from@a.dev", ["to@a.dev"], i.to_string()))
+            .collect::<Vec<_>>();
+
+        let start = Instant::now();
+        let results = resend.emails.send_many(&emails, 3).await;
+        assert!(start.elapsed() < Duration::from_millis(1100));
+
+        let ids = results
+            .into_iter()
+            .map(|result| result.map(|response| response.id.to_string()))
+            .collect::<Result<Vec<_>>>()?;
+        let expected = (0..8).map(|i| i.to_string()).collect::<Vec<_>>();
+        assert_eq!(ids, expected);
+        assert_eq!(server.requests().len(), 8);
+
+        Ok(())
+    }
+
+    #[maybe_async::test(feature = "blocking", async(not(feature = "blocking"), tokio::test))]
+    async fn send_many_backs_off() -> Result<()> {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::time::{Duration, Instant};
+
+        let requests = AtomicUsize::new(0);
+        let server = MockServer::start(move |_| {
+            if requests.fetch_add(1, Ordering::SeqCst) < 3 {
+                let body = r#"{"statusCode":429,"message":"Too many requests","name":"rate_limit_exceeded"}"#;
+                return MockResponse::json(429, body);
+            }
+            MockResponse::json(200, r#"{"id":"1"}"#)
+        });
+        let resend = Resend::builder("re_123").base_url(server.url()).build()?;
+
+        let email = CreateEmailBaseOptions::new("from@a.dev", ["to@a.dev"], "Subject");
+        let emails = vec![email; 6];
+
+        let start = Instant::now();
+        let results = resend.emails.send_many(&emails, 3).await;
+        assert!(start.elapsed() >= Duration::from_millis(100));
+
+        assert_eq!(results.len(), 6);
+        assert!(results.iter().all(Result::is_ok));
+        assert_eq!(server.requests().len(), 9);
+
+        Ok(())
+    }
+
+    #[maybe_async::test(feature = "blocking", async(not(feature = "blocking"), tokio::test))]
+    async fn send_many_adapts_concurrency() -> Result<()> {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::{Arc, Mutex};
+        use std::time::Duration;
+
+        // Records how many other requests are in flight when each request arrives.
+        let in_flight = AtomicUsize::new(0);
+        let arrivals = Arc::new(Mutex::new(Vec::new()));
+        let recorded = arrivals.clone();
+        let server = MockServer::start(move |_| {
+            let others = in_flight.fetch_add(1, Ordering::SeqCst);
+            recorded.lock().expect("not poisoned").push(others);
+            std::thread::sleep(Duration::from_millis(20));
+            let _ = in_flight.fetch_sub(1, Ordering::SeqCst);
+
+            let body =
+                r#"{"statusCode":429,"message":"Too many requests","name":"rate_limit_exceeded"}"#;
+            MockResponse::json(429, body).with_header("retry-after", "0")
+        });
+        let resend = Resend::builder("re_123")
+            .base_url(server.url())
+            .rate_limit(false)
+            .build()?;
+
+        let email = CreateEmailBaseOptions::new("from@a.dev", ["to@a.dev"], "Subject");
+        let emails = vec![email; 4];
+        let results = resend.emails.send_many(&emails, 4).await;
+        assert!(results.iter().all(Result::is_err));
+
+        // Every email is tried 6 times, the concurrency quickly drops to a single request.
+        let arrivals = arrivals.lock().expect("not poisoned").clone();
+        assert_eq!(arrivals.len(), 24);
+        assert!(arrivals[12..].iter().all(|others| *others == 0));
+
+        Ok(())
+    }
+
+    #[maybe_async::test(feature = "blocking", async(not(feature = "blocking"), tokio::test))]
+    async fn send_many_uses_retry_after() -> Result<()> {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::time::{Duration, Instant};
+
+        let requests = AtomicUsize::new(0);
+        let server = MockServer::start(move |_| {
+            if requests.fetch_add(1, Ordering::SeqCst) < 3 {
+                let body = r#"{"statusCode":429,"message":"Too many requests","name":"rate_limit_exceeded"}"#;
+                return MockResponse::json(429, body).with_header("retry-after", "0");
+            }
+            MockResponse::json(200, r#"{"id":"1"}"#)
+        });
+        let resend = Resend::builder("re_123").base_url(server.url()).build()?;
+
+        let email = CreateEmailBaseOptions::new("from@a.dev", ["to@a.dev"], "Subject");
+
+        // The exponential backoff would wait at least 700ms.
+        let start = Instant::now();
+        let results = resend.emails.send_many(&[email], 1).await;
+        assert!(start.elapsed() < Duration::from_millis(500));
+
+        assert!(results.iter().all(Result::is_ok));
+        assert_eq!(server.requests().len(), 4);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[cfg(not(feature = "blocking"))]
+    async fn wait_for_event() -> Result<()> {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::time::Duration;
+
+        let polls = AtomicUsize::new(0);
+        let server = MockServer::start(move |_| {
+            let last_event = match polls.fetch_add(1, Ordering::SeqCst) {
+                0 => "sent",
+                _ => "delivered",
+            };
+            let email = serde_json::json!({
+                "id": "49a3999c",
+                "to": ["to@a.dev"],
+                "from": "from@a.dev",
+                "created_at": "2023-04-03T22:13:42.674981+00:00",
+                "subject": "Subject",
+                "html": null,
+                "text": "",
+                "bcc": [],
+                "cc": [],
+                "reply_to": null,
+                "last_event": last_event,
+            });
+            MockResponse::json(200, &email.to_string())
+        });
+        let resend = Resend::builder("re_123").base_url(server.url()).build()?;
+
+        let (timeout, interval) = (Duration::from_secs(5), Duration::from_millis(10));
+        let email = resend
+            .emails
+            .wait_for_event("49a3999c", EmailEvent::Delivered, timeout, interval)
+            .await?;
+
+        assert_eq!(email.last_event, EmailEvent::Delivered.as_str());
+        assert_eq!(server.requests().len(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "rfc2047")]
+    fn rfc2047_display_name() {
+        let from = EmailAddress::new("team@acme.dev").with_name("Café Acme");
+        let reply = EmailAddress::new("support@acme.dev").with_name("Acme Support");
+        let email = CreateEmailBaseOptions::empty()
+            .with_encoded_from(&from)
+            .with_encoded_reply(&reply);
+
+        assert_eq!(email.from, "=?UTF-8?B?Q2Fmw6kgQWNtZQ==?= <team@acme.dev>");
+        // ASCII names don't need to be encoded.
+        assert_eq!(
+            email.reply_to,
+            Some(vec!["Acme Support <support@acme.dev>".to_owned()])
+        );
+
+        // Long names are split into multiple encoded words, without splitting characters.
+        let from = EmailAddress::new("team@acme.dev").with_name(&"é".repeat(30));
+        let encoded = from.to_rfc2047_string();
+        let words = encoded.trim_end_matches(" <team@acme.dev>").split(' ');
+        assert!(words.clone().all(|word| word.len() <= 75));
+        assert_eq!(words.count(), 2);
+    }
+
+    #[test]
+    #[cfg(feature = "html2text")]
+    fn auto_text() {
+        let html = r#"<h1>Welcome!</h1><p>Read the <a href="https://acme.dev/docs">docs</a>.</p>"#;
+        let email = CreateEmailBaseOptions::empty()
+            .with_html(html)
+            .with_auto_text();
+
+        let text = email.text.unwrap_or_default();
+        assert!(text.contains("Welcome!"));
+        assert!(text.contains("Read the [docs][1]."));
+        assert!(text.contains("[1]: https://acme.dev/docs"));
+        assert!(!text.contains('<'));
+
+        // An explicit text version is kept.
+        let email = CreateEmailBaseOptions::empty()
+            .with_html(html)
+            .with_text("Welcome")
+            .with_auto_text();
+        assert_eq!(email.text.as_deref(), Some("Welcome"));
+    }
+
+    #[test]
+    fn clone_is_deep() {
+        let original = CreateEmailBaseOptions::new("from@a.dev", ["to@a.dev"], "Subject")
+            .with_html("<p>Hello</p>")
+            .with_text("Hello")
+            .with_cc("cc@a.dev")
+            .with_bcc("bcc@a.dev")
+            .with_reply("reply@a.dev")
+            .with_header("X-Entity-Ref-ID", "123")
+            .with_attachment(Attachment::from_path("https://acme.dev/invoice.pdf"))
+            .with_tag(Tag::new("category", "welcome"));
+
+        let mut clone = original.clone().with_tag(Tag::new("region", "eu"));
+        if let Some(tags) = clone.tags.as_mut() {
+            tags[0].value = "changed".to_owned();
+        }
+        if let Some(headers) = clone.headers.as_mut() {
+            let _ = headers.insert("X-Entity-Ref-ID".to_owned(), "456".to_owned());
+        }
+        clone.to.push("other@a.dev".to_owned());
+
+        let tags = original.tags.unwrap_or_default();
+        assert_eq!(tags.len(), 1);
+        assert_eq!(tags[0].value, "welcome");
+        let headers = original.headers.unwrap_or_default();
+        assert_eq!(
+            headers.get("X-Entity-Ref-ID").map(String::as_str),
+            Some("123")
+        );
+        assert_eq!(original.to, ["to@a.dev"]);
+    }
+
+    #[test]
+    fn attachment_disposition() -> serde_json::Result<()> {
+        let inline = Attachment::from_path("https://acme.dev/logo.png")
+            .with_disposition(Disposition::Inline);
+        let expected = serde_json::json!({
+            "path": "https://acme.dev/logo.png",
+            "disposition": "inline"
+        });
+        assert_eq!(serde_json::to_value(inline)?, expected);
+
+        let attachment = Attachment::from_path("https://acme.dev/invoice.pdf")
+            .with_disposition(Disposition::Attachment);
+        let expected = serde_json::json!({
+            "path": "https://acme.dev/invoice.pdf",
+            "disposition": "attachment"
+        });
+        assert_eq!(serde_json::to_value(attachment)?, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn deduplicated_recipients() {
+        let email = CreateEmailBaseOptions::new("from@a.dev", ["to@a.dev", "b@a.dev"], "Subject")
+            .with_cc("TO@a.dev")
+            .with_cc("c@a.dev")
+            .with_bcc("b@a.dev");
+
+        assert_eq!(
+            email.validate_unique_recipients(),
+            Err(ValidationError::DuplicateRecipient("TO@a.dev".to_owned()))
+        );
+
+        let email = email.with_deduplicated_recipients();
+        assert_eq!(email.cc.as_deref(), Some(["c@a.dev".to_owned()].as_slice()));
+        assert_eq!(email.bcc.as_deref(), Some([].as_slice()));
+        assert_eq!(email.validate_unique_recipients(), Ok(()));
+    }
+
+    #[test]
+    fn with_tags_from_map() -> std::result::Result<(), ValidationError> {
+        use std::collections::HashMap;
+
+        let email = CreateEmailBaseOptions::new("from@a.dev", ["to@a.dev"], "Subject");
+        let map = HashMap::from([
+            ("region".to_owned(), "eu".to_owned()),
+            ("category".to_owned(), "welcome".to_owned()),
+        ]);
+
+        let email = email.with_tags_from_map(map)?;
+        let tags: Vec<_> = email
+            .tags
+            .iter()
+            .flatten()
+            .map(|tag| (tag.name.as_str(), tag.value.as_str()))
+            .collect();
+        assert_eq!(tags, [("category", "welcome"), ("region", "eu")]);
+
+        let map = HashMap::from([("category".to_owned(), "not valid".to_owned())]);
+        assert_eq!(
+            email.with_tags_from_map(map).err(),
+            Some(ValidationError::InvalidTag("category".to_owned()))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn with_attachments() {
+        let files = ["a.pdf", "b.pdf", "c.pdf"]
+            .map(|name| Attachment::from_path(&format!("https://acme.dev/{name}")));
+        let email = CreateEmailBaseOptions::new("from@a.dev", ["to@a.dev"], "Subject")
+            .with_attachment(Attachment::from_content(vec![1, 2, 3]))
+            .with_attachments(files);
+
+        let paths = email
+            .attachments
+            .iter()
+            .flatten()
+            .map(|attachment| match &attachment.content_or_path {
+                ContentOrPath::Path(path) => Some(path.as_str()),
+                ContentOrPath::Content(_) | ContentOrPath::File(_) => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            paths,
+            [
+                None,
+                Some("https://acme.dev/a.pdf"),
+                Some("https://acme.dev/b.pdf"),
+                Some("https://acme.dev/c.pdf"),
+            ]
+        );
+    }
+
+    #[test]
+    fn to_request_json() -> serde_json::Result<()> {
+        let email = CreateEmailBaseOptions::new("from@a.dev", ["to@a.dev"], "Subject")
+            .with_html("<p>Hello</p>")
+            .with_text("Hello")
+            .with_cc("cc@a.dev")
+            .with_bcc("bcc@a.dev")
+            .with_reply("reply@a.dev")
+            .with_header("X-Entity-Ref-ID", "123")
+            .with_attachment(Attachment::from_path("https://acme.dev/invoice.pdf"))
+            .with_tag(Tag::new("category", "confirm_email"))
+            .with_scheduled_at("in 1 hour");
+
+        let expected = serde_json::json!({
+            "from": "from@a.dev",
+            "to": ["to@a.dev"],
+            "subject": "Subject",
+            "html": "<p>Hello</p>",
+            "text": "Hello",
+            "cc": ["cc@a.dev"],
+            "bcc": ["bcc@a.dev"],
+            "reply_to": ["reply@a.dev"],
+            "headers": { "X-Entity-Ref-ID": "123" },
+            "attachments": [{ "path": "https://acme.dev/invoice.pdf" }],
+            "tags": [{ "name": "category", "value": "confirm_email" }],
+            "scheduled_at": "in 1 hour"
+        });
+        assert_eq!(email.to_request_json()?, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_limits() {
+        use crate::limits::{MAX_ATTACHMENT_BYTES, MAX_RECIPIENTS};
+
+        let to = (0..MAX_RECIPIENTS)
+            .map(|i| format!("{i}@a.dev"))
+            .collect::<Vec<_>>();
+        let email = CreateEmailBaseOptions::new("from@a.dev", to, "Subject");
+        assert!(email.validate().is_ok());
+        assert_eq!(
+            email.with_to("extra@a.dev").validate(),
+            Err(ValidationError::TooManyRecipients {
+                count: MAX_RECIPIENTS + 1,
+                max: MAX_RECIPIENTS
+            })
+        );
+
+        let email = CreateEmailBaseOptions::new("from@a.dev", ["to@a.dev"], "Subject")
+            .with_attachment(vec![0; MAX_ATTACHMENT_BYTES])
+            .with_attachment(Attachment::from_path("https://acme.dev/invoice.pdf"));
+        assert!(email.validate().is_ok());
+        assert_eq!(
+            email.with_attachment(vec![0; 1]).validate(),
+            Err(ValidationError::AttachmentsTooLarge {
+                size: MAX_ATTACHMENT_BYTES + 1,
+                max: MAX_ATTACHMENT_BYTES
+            })
+        );
+    }
+
+    #[test]
+    fn with_html_and_images() {
+        use std::collections::HashMap;
+
+        let html = r#"<img src="cid:logo"><img src='cid:banner'><img src="cid:missing">"#;
+        let images = HashMap::from([
+            ("logo".to_owned(), vec![1]),
+            ("banner".to_owned(), vec![2]),
+            ("unused".to_owned(), vec![3]),
+        ]);
+
+        let email = CreateEmailBaseOptions::new("from@a.dev", ["to@a.dev"], "Subject")
+            .with_html_and_images(html, images);
+        assert_eq!(email.html.as_deref(), Some(html));
+
+        let attachments = email.attachments.unwrap_or_default();
+        let ids = attachments
+            .iter()
+            .map(|attachment| attachment.content_id.as_deref())
+            .collect::<Vec<_>>();
+        assert_eq!(ids, [Some("logo"), Some("banner")]);
+        assert!(attachments
+            .iter()
+            .all(|attachment| attachment.disposition == Some(Disposition::Inline)));
+    }
+
+    #[test]
+    fn from_tuple() -> serde_json::Result<()> {
+        let email = CreateEmailBaseOptions::from(("from@a.dev", "to@a.dev", "Subject"))
+            .with_text("Hello World!");
+
+        let expected = serde_json::json!({
+            "from": "from@a.dev",
+            "to": ["to@a.dev"],
+            "subject": "Subject",
+            "text": "Hello World!"
+        });
+        assert_eq!(serde_json::to_value(email)?, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn with_headers_from() {
+        use reqwest::header::{HeaderMap, HeaderValue};
+
+        let mut map = HeaderMap::new();
+        let _ = map.insert("X-Entity-Ref-ID", HeaderValue::from_static("123"));
+        let _ = map.insert(
+            "List-Unsubscribe",
+            HeaderValue::from_static("<https://a.dev>"),
+        );
+        let _ = map.insert(
+            "X-Binary",
+            HeaderValue::from_bytes(b"\xff").expect("valid value"),
+        );
+
+        let email = CreateEmailBaseOptions::new("from@a.dev", ["to@a.dev"], "Subject")
+            .with_headers_from(&map);
+
+        let headers = email.headers.unwrap_or_default();
+        assert_eq!(headers.len(), 2);
+        // `HeaderMap` lowercases names.
+        assert_eq!(
+            headers.get("x-entity-ref-id").map(String::as_str),
+            Some("123")
+        );
+        assert_eq!(
+            headers.get("list-unsubscribe").map(String::as_str),
+            Some("<https://a.dev>")
+        );
+    }
+
+    #[test]
+    fn attachment_from_parts() {
+        let attachment =
+            Attachment::from_parts(Some("invoice.pdf"), Some("application/pdf"), vec![1, 2, 3]);
+
+        assert!(matches!(
+            attachment.content_or_path,
+            ContentOrPath::Content(ref content) if content == &[1, 2, 3]
+        ));
+        assert_eq!(attachment.filename.as_deref(), Some("invoice.pdf"));
+        assert_eq!(attachment.content_type.as_deref(), Some("application/pdf"));
+
+        let attachment = Attachment::from_parts(None, None, Vec::new());
+        assert!(attachment.filename.is_none() && attachment.content_type.is_none());
+    }
+
+    #[test]
+    fn attachment_from_file() -> std::io::Result<()> {
+        use super::types::FILE_CHUNK;
+
+        // Spans two full read chunks and a partial one.
+        let len = usize::try_from(FILE_CHUNK * 2 + 1).expect("fits");
+        let content = (0..len)
+            .map(|i| u8::try_from(i % 251).unwrap_or_default())
+            .collect::<Vec<_>>();
+        let path = std::env::temp_dir().join(format!("resend-attachment-{}", std::process::id()));
+
+        // Only read once serialized.
+        let attachment = Attachment::from_file(&path).with_filename("a.bin");
+        std::fs::write(&path, &content)?;
+
+        let from_file = serde_json::to_value(attachment);
+        let from_content = serde_json::to_value(Attachment::from(content).with_filename("a.bin"));
+        std::fs::remove_file(&path)?;
+        let (from_file, from_content) = (from_file?, from_content?);
+        assert_eq!(from_file, from_content);
+
+        // Padded once at the end, not at the end of every chunk.
+        let encoded = from_file["content"].as_str().unwrap_or_default();
+        assert_eq!(encoded.len(), len.div_ceil(3) * 4);
+        assert_eq!(encoded.trim_end_matches('=').find('='), None);
+
+        let missing = serde_json::to_value(Attachment::from_file(&path));
+        assert!(missing.is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn attachment_from_file_too_large() -> std::io::Result<()> {
+        use crate::limits::MAX_ATTACHMENT_BYTES;
+
+        let path = std::env::temp_dir().join(format!("resend-large-{}", std::process::id()));
+        // Sparse, doesn't take up any space.
+        std::fs::File::create(&path)?.set_len(MAX_ATTACHMENT_BYTES as u64 + 1)?;
+
+        let email = CreateEmailBaseOptions::new("from@a.dev", ["to@a.dev"], "Subject")
+            .with_attachment(Attachment::from_file(&path));
+        let result = email.validate();
+        std::fs::remove_file(&path)?;
+
+        assert_eq!(
+            result,
+            Err(ValidationError::AttachmentsTooLarge {
+                size: MAX_ATTACHMENT_BYTES + 1,
+                max: MAX_ATTACHMENT_BYTES
+            })
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_id() {
+        let id = "4ef9a417-02e9-4d39-ad75-9611e0fcc33c".parse::<EmailId>();
+        assert!(id.is_ok_and(|id| id.as_ref() == "4ef9a417-02e9-4d39-ad75-9611e0fcc33c"));
+    }
+
+    #[test]
+    fn email_json_round_trip() -> serde_json::Result<()> {
+        let response = r#"{
+            "object": "email",
+            "id": "4ef9a417-02e9-4d39-ad75-9611e0fcc33c",
+            "to": ["delivered@resend.dev"],
+            "from": "Acme <onboarding@resend.dev>",
+            "created_at": "2023-04-03T22:13:42.674981+00:00",
+            "subject": "Hello World",
+            "html": "Congrats on sending your <strong>first email</strong>!",
+            "text": "",
+            "bcc": [],
+            "cc": [],
+            "reply_to": null,
+            "last_event": "delivered"
+        }"#;
+        let email = Email::from_json(response)?;
+
+        let parsed = Email::from_json(&email.to_json()?)?;
+        assert_eq!(parsed.id.as_ref(), email.id.as_ref());
+        assert_eq!(parsed.subject, email.subject);
+
+        Ok(())
+    }
+
+    #[test]
+    fn reply_to_string_or_array() -> serde_json::Result<()> {
+        let email = |reply_to: &str| {
+            let json = format!(
+                r#"{{
+                    "id": "4ef9a417-02e9-4d39-ad75-9611e0fcc33c",
+                    "to": ["delivered@resend.dev"],
+                    "from": "Acme <onboarding@resend.dev>",
+                    "created_at": "2023-04-03T22:13:42.674981+00:00",
+                    "subject": "Hello World",
+                    "html": null,
+                    "text": "",
+                    "bcc": [],
+                    "cc": [],
+                    {reply_to}
+                    "last_event": "delivered"
+                }}"#
+            );
+            Email::from_json(&json).map(|email| email.reply_to)
+        };
+
+        let one = email(r#""reply_to": "a@a.dev","#)?;
+        assert_eq!(one, Some(vec!["a@a.dev".to_owned()]));
+
+        let many = email(r#""reply_to": ["a@a.dev", "b@a.dev"],"#)?;
+        assert_eq!(many, Some(vec!["a@a.dev".to_owned(), "b@a.dev".to_owned()]));
+
+        assert_eq!(email(r#""reply_to": null,"#)?, None);
+        assert_eq!(email("")?, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn html_only_email() -> serde_json::Result<()> {
+        let response = r#"{
+            "object": "email",
+            "id": "4ef9a417-02e9-4d39-ad75-9611e0fcc33c",
+            "to": ["delivered@resend.dev"],
+            "from": "Acme <onboarding@resend.dev>",
+            "created_at": "2023-04-03T22:13:42.674981+00:00",
+            "subject": "Hello World",
+            "html": "<p>Hello World</p>",
+            "text": null,
+            "bcc": [],
+            "cc": [],
+            "reply_to": null,
+            "last_event": "delivered"
+        }"#;
+        let email = Email::from_json(response)?;
+        assert!(email.text.is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn response_warnings() -> serde_json::Result<()> {
+        let response: CreateEmailResponse = serde_json::from_str(
+            r#"{"id":"49a3999c","warnings":["`contentType` is deprecated, use `content_type`"]}"#,
+        )?;
+        assert_eq!(response.id.as_ref(), "49a3999c");
+        assert_eq!(
+            response.warnings,
+            ["`contentType` is deprecated, use `content_type`"]
+        );
+
+        let response: CreateEmailResponse = serde_json::from_str(r#"{"id":"49a3999c"}"#)?;
+        assert!(response.warnings.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn response_scheduled_at() -> serde_json::Result<()> {
+        let response: CreateEmailResponse = serde_json::from_str(
+            r#"{"id":"49a3999c","scheduled_at":"2024-08-05T12:52:01.858+00:00"}"#,
+        )?;
+        assert_eq!(
+            response.scheduled_at.as_deref(),
+            Some("2024-08-05T12:52:01.858+00:00")
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn missing_cc_and_bcc() -> serde_json::Result<()> {
+        let response = r#"{
+            "object": "email",
+            "id": "4ef9a417-02e9-4d39-ad75-9611e0fcc33c",
+            "to": ["delivered@resend.dev"],
+            "from": "Acme <onboarding@resend.dev>",
+            "created_at": "2023-04-03T22:13:42.674981+00:00",
+            "subject": "Hello World",
+            "html": "<p>Hello World</p>",
+            "text": "Hello World",
+            "reply_to": null,
+            "last_event": "delivered"
+        }"#;
+        let email = Email::from_json(response)?;
+        assert!(email.cc.is_empty());
+        assert!(email.bcc.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn wire_names() -> serde_json::Result<()> {
+        let email = CreateEmailBaseOptions::new(
+            "Acme <onboarding@resend.dev>",
+            ["delivered@resend.dev"],
+            "hello world",
+        )
+        .with_html("<p>it works!</p>")
+        .with_text("it works!")
+        .with_bcc("bcc@resend.dev")
+        .with_cc("cc@resend.dev")
+        .with_reply("onboarding@resend.dev")
+        .with_header("X-Entity-Ref-ID", "123")
+        .with_attachment(
+            Attachment::from_path("https://resend.com/static/sample/invoice.pdf")
+                .with_filename("invoice.pdf")
+                .with_content_type("application/pdf"),
+        )
+        .with_attachment(Attachment::from_content(vec![1, 2, 3]))
+        .with_tag(Tag::new("category", "confirm_email"));
+
+        let expected = serde_json::json!({
+            "from": "Acme <onboarding@resend.dev>",
+            "to": ["delivered@resend.dev"],
+            "subject": "hello world",
+            "html": "<p>it works!</p>",
+            "text": "it works!",
+            "bcc": ["bcc@resend.dev"],
+            "cc": ["cc@resend.dev"],
+            "reply_to": ["onboarding@resend.dev"],
+            "headers": { "X-Entity-Ref-ID": "123" },
+            "attachments": [
+                {
+                    "path": "https://resend.com/static/sample/invoice.pdf",
+                    "filename": "invoice.pdf",
+                    "content_type": "application/pdf"
+                },
+                { "content": "AQID" }
+            ],
+            "tags": [{ "name": "category", "value": "confirm_email" }]
+        });
+        assert_eq!(serde_json::to_value(&email)?, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn email_address_quoting() {
+        let address = |name: &str| {
+            EmailAddress::new("john@acme.dev")
+                .with_name(name)
+                .to_string()
+        };
+
+        assert_eq!(
+            EmailAddress::new("john@acme.dev").to_string(),
+            "john@acme.dev"
+        );
+        assert_eq!(address("John Doe"), "John Doe <john@acme.dev>");
+        assert_eq!(address("Café Acme"), "Café Acme <john@acme.dev>");
+        assert_eq!(address("Doe, John"), r#""Doe, John" <john@acme.dev>"#);
+        assert_eq!(
+            address(r#"John "JD" Doe"#),
+            r#""John \"JD\" Doe" <john@acme.dev>"#
+        );
+
+        let from = EmailAddress::new("john@acme.dev").with_name("Doe, John");
+        let email = CreateEmailBaseOptions::new(from, ["to@a.dev"], "Subject");
+        let json = serde_json::to_value(&email).unwrap_or_default();
+        assert_eq!(json["from"], r#""Doe, John" <john@acme.dev>"#);
+    }
+
+    #[test]
+    fn email_address_parsing() {
+        let parse = |s: &str| s.parse::<EmailAddress>();
+
+        assert_eq!(
+            parse("john@acme.dev"),
+            Ok(EmailAddress::new("john@acme.dev"))
+        );
+        assert_eq!(
+            parse("John Doe <john@acme.dev>"),
+            Ok(EmailAddress::new("john@acme.dev").with_name("John Doe"))
+        );
+        assert_eq!(
+            parse(r#""Doe, \"JD\" <John>" <john@acme.dev>"#),
+            Ok(EmailAddress::new("john@acme.dev").with_name(r#"Doe, "JD" <John>"#))
+        );
+
+        for invalid in [
+            "",
+            "john",
+            "@acme.dev",
+            "john@",
+            "John <john@acme.dev",
+            "a b@acme.dev",
+        ] {
+            assert_eq!(
+                parse(invalid),
+                Err(ValidationError::InvalidAddress(invalid.to_owned()))
+            );
+        }
+    }
+
+    #[test]
+    fn email_address_round_trip() {
+        // Deterministic xorshift, to explore arbitrary names without a property testing crate.
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+        let alphabet: Vec<char> = r#"aZ0 é"\,<>@().;:!#'_-"#.chars().collect();
+        let mut arbitrary = |len: u64| -> String {
+            (0..next() % len)
+                .map(|_| alphabet[usize::try_from(next()).unwrap_or_default() % alphabet.len()])
+                .collect()
+        };
+
+        for _ in 0..2000 {
+            // Never panics, whatever the input.
+            let _ = arbitrary(24).parse::<EmailAddress>();
+
+            let name = arbitrary(16);
+            if name.is_empty() {
+                continue;
+            }
+            let address = EmailAddress::new("john@acme.dev").with_name(&name);
+            assert_eq!(address.to_string().parse(), Ok(address), "{name:?}");
+        }
+    }
+
+    #[tokio::test]
+    #[cfg(not(feature = "blocking"))]
+    async fn send_raw() -> Result<()> {
+        let server = MockServer::start(|_| {
+            MockResponse::json(200, r#"{"id":"1"}"#).with_header("x-custom", "value")
+        });
+        let resend = Resend::builder("re_123").base_url(server.url()).build()?;
+
+        let email = CreateEmailBaseOptions::new("from@a.dev", ["to@a.dev"], "Subject");
+        let response = resend.emails.send_raw(&email).await?;
+
+        let header = response.headers().get("x-custom");
+        assert_eq!(header.and_then(|value| value.to_str().ok()), Some("value"));
+        assert_eq!(response.text().await?, r#"{"id":"1"}"#);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[cfg(not(feature = "blocking"))]
+    async fn sends_html_and_text() -> Result<()> {
+        let server = MockServer::start(|_| MockResponse::json(200, r#"{"id":"1"}"#));
+        let resend = Resend::builder("re_123").base_url(server.url()).build()?;
+
+        let email = CreateEmailBaseOptions::new("from@a.dev", ["to@a.dev"], "Subject")
+            .with_html("<p>Hello World!</p>")
+            .with_text("Hello World!");
+        let _ = resend.emails.send(email).await?;
+
+        let body: serde_json::Value =
+            serde_json::from_slice(&server.requests()[0].body).unwrap_or_default();
+        assert_eq!(body["html"], "<p>Hello World!</p>");
+        assert_eq!(body["text"], "Hello World!");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[cfg(not(feature = "blocking"))]
+    async fn sends_headers_as_object() -> Result<()> {
+        let server = MockServer::start(|_| MockResponse::json(200, r#"{"id":"1"}"#));
+        let resend = Resend::builder("re_123").base_url(server.url()).build()?;
+
+        let email = CreateEmailBaseOptions::new("from@a.dev", ["to@a.dev"], "Subject")
+            .with_header("X-Entity-Ref-ID", "123")
+            .with_header("List-Unsubscribe", "<https://a.dev/unsubscribe>");
+        let _ = resend.emails.send(email).await?;
+
+        // The API expects an object, not an array of `{name, value}` pairs.
+        let body: serde_json::Value =
+            serde_json::from_slice(&server.requests()[0].body).unwrap_or_default();
+        let expected = serde_json::json!({
+            "X-Entity-Ref-ID": "123",
+            "List-Unsubscribe": "<https://a.dev/unsubscribe>",
+        });
+        assert_eq!(body["headers"], expected);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[cfg(all(feature = "tracing", not(feature = "blocking")))]
+    async fn warn_on_missing_text() -> Result<()> {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        use tracing::span::{Attributes, Id, Record};
+        use tracing::{Event, Level, Metadata, Subscriber};
+
+        /// Counts warnings.
+        struct WarnCounter(Arc<AtomicUsize>);
+
+        impl Subscriber for WarnCounter {
+            fn enabled(&self, _: &Metadata<'_>) -> bool {
+                true
+            }
+            fn new_span(&self, _: &Attributes<'_>) -> Id {
+                Id::from_u64(1)
+            }
+            fn record(&self, _: &Id, _: &Record<'_>) {}
+            fn record_follows_from(&self, _: &Id, _: &Id) {}
+            fn event(&self, event: &Event<'_>) {
+                if *event.metadata().level() == Level::WARN {
+                    let _ = self.0.fetch_add(1, Ordering::SeqCst);
+                }
+            }
+            fn enter(&self, _: &Id) {}
+            fn exit(&self, _: &Id) {}
+        }
+
+        let warnings = Arc::new(AtomicUsize::new(0));
+        let _guard = tracing::subscriber::set_default(WarnCounter(warnings.clone()));
+
+        let server = MockServer::start(|_| MockResponse::json(200, r#"{"id":"1"}"#));
+        let resend = Resend::builder("re_123")
+            .base_url(server.url())
+            .warn_on_missing_text(true)
+            .build()?;
+        let email = CreateEmailBaseOptions::new("from@a.dev", ["to@a.dev"], "Subject");
+
+        let _ = resend
+            .emails
+            .send(email.clone().with_html("<p>Hi</p>"))
+            .await?;
+        assert_eq!(warnings.load(Ordering::SeqCst), 1);
+
+        let _ = resend
+            .emails
+            .send(email.with_text_and_html("Hi", "<p>Hi</p>"))
+            .await?;
+        assert_eq!(warnings.load(Ordering::SeqCst), 1);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[cfg(not(feature = "blocking"))]
+    async fn send_custom() -> Result<()> {
+        #[derive(serde::Serialize)]
+        struct CustomEmail {
+            #[serde(flatten)]
+            email: CreateEmailBaseOptions,
+            topic_id: &'static str,
+        }
+
+        let server = MockServer::start(|_| MockResponse::json(200, r#"{"id":"1"}"#));
+        let resend = Resend::builder("re_123").base_url(server.url()).build()?;
+
+        let email = CustomEmail {
+            email: CreateEmailBaseOptions::new("from@a.dev", ["to@a.dev"], "Subject"),
+            topic_id: "b6d24b8e",
+        };
+        let id = resend.emails.send_custom(&email).await?.id;
+        assert_eq!(id.as_ref(), "1");
+
+        let body: serde_json::Value =
+            serde_json::from_slice(&server.requests()[0].body).unwrap_or_default();
+        assert_eq!(body["topic_id"], "b6d24b8e");
+        assert_eq!(body["subject"], "Subject");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[cfg(not(feature = "blocking"))]
+    async fn get_html_response() -> Result<()> {
+        let server = MockServer::start(|_| {
+            MockResponse::html(200, "<html><body>Service Unavailable</body></html>")
+        });
+        let resend = Resend::builder("re_123").base_url(server.url()).build()?;
+
+        let result = resend.emails.get("49a3999c").await;
+        assert!(matches!(
+            result,
+            Err(Error::UnexpectedContentType { content_type, body_snippet })
+                if content_type == "text/html" && body_snippet.contains("Service Unavailable")
+        ));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[cfg(not(feature = "blocking"))]
+    async fn try_get() -> Result<()> {
+        let server = MockServer::start(|request| {
+            if request.path.ends_with("missing") {
+                let body = r#"{"statusCode":404,"message":"Email not found","name":"not_found"}"#;
+                return MockResponse::json(404, body);
+            }
+            if request.path.ends_with("broken") {
+                let body = r#"{"statusCode":500,"message":"","name":"internal_server_error"}"#;
+                return MockResponse::json(500, body);
+            }
+
+            let email = serde_json::json!({
+                "id": "49a3999c",
+                "to": ["to@a.dev"],
+                "from": "from@a.dev",
+                "created_at": "2023-04-03T22:13:42.674981+00:00",
+                "subject": "Subject",
+                "html": null,
+                "text": null,
+                "bcc": [],
+                "cc": [],
+                "reply_to": null,
+                "last_event": "sent",
+            });
+            MockResponse::json(200, &email.to_string())
+        });
+        let resend = Resend::builder("re_123").base_url(server.url()).build()?;
+
+        let email = resend.emails.try_get("49a3999c").await?;
+        assert!(email.is_some_and(|email| email.id.as_ref() == "49a3999c"));
+
+        assert!(resend.emails.try_get("missing").await?.is_none());
+        assert!(resend.emails.try_get("broken").await.is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn stable_idempotency_key() -> serde_json::Result<()> {
+        let email = CreateEmailBaseOptions::new("from@a.dev", ["to@a.dev"], "Subject")
+            .with_header("X-A", "1")
+            .with_header("X-B", "2");
+        let reordered = CreateEmailBaseOptions::new("from@a.dev", ["to@a.dev"], "Subject")
+            .with_header("X-B", "2")
+            .with_header("X-A", "1");
+
+        // Pinned so that the key of an email never changes between releases.
+        let key = idempotency_key(&serde_json::to_vec(&email)?);
+        assert_eq!(key, "auto-40994cb6c4d7056b6e670529cb1bac2d");
+        assert_eq!(idempotency_key(&serde_json::to_vec(&reordered)?), key);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[cfg(not(feature = "blocking"))]
+    async fn auto_idempotency() -> Result<()> {
+        let server = MockServer::start(|_| MockResponse::json(200, r#"{"id":"1"}"#));
+        let email = CreateEmailBaseOptions::new("from@a.dev", ["to@a.dev"], "Subject");
+
+        let resend = Resend::builder("re_123").base_url(server.url()).build()?;
+        let _ = resend.emails.send_ref(&email).await?;
+
+        let resend = Resend::builder("re_123")
+            .base_url(server.url())
+            .auto_idempotency(true)
+            .build()?;
+        let _ = resend.emails.send_ref(&email).await?;
+        let _ = resend.emails.send_ref(&email).await?;
+        let _ = resend.emails.send(email.with_subject("Other")).await?;
+
+        let requests = server.requests();
+        let keys = requests
+            .iter()
+            .map(|request| request.header("idempotency-key"))
+            .collect::<Vec<_>>();
+        assert!(keys[0].is_none());
+        assert!(keys[1].is_some_and(|key| key.starts_with("auto-")));
+        assert_eq!(keys[1], keys[2]);
+        assert_ne!(keys[2], keys[3]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[cfg(not(feature = "blocking"))]
+    async fn cancel_many() -> Result<()> {
+        let server = MockServer::start(|request| {
+            if request.path.contains("sent") {
+                let body = r#"{"statusCode":422,"message":"Email already sent.","name":"validation_error"}"#;
+                return MockResponse::json(422, body);
+            }
+            MockResponse::json(200, r#"{"object":"email","id":"1"}"#)
+        });
+        let resend = Resend::builder("re_123").base_url(server.url()).build()?;
+
+        let results = resend.emails.cancel_many(&["a", "sent", "c"]).await;
+        assert!(matches!(
+            results.as_slice(),
+            [Ok(()), Err(Error::Resend(_)), Ok(())]
+        ));
+
+        let paths = server.requests().into_iter().map(|r| r.path);
+        assert_eq!(
+            paths.collect::<Vec<_>>(),
+            [
+                "/emails/a/cancel",
+                "/emails/sent/cancel",
+                "/emails/c/cancel"
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[cfg(not(feature = "blocking"))]
+    async fn reschedule() -> Result<()> {
+        let server =
+            MockServer::start(|_| MockResponse::json(200, r#"{"object":"email","id":"49a3999c"}"#));
+        let resend = Resend::builder("re_123").base_url(server.url()).build()?;
+
+        let updated = resend.emails.reschedule("49a3999c", "in 1 hour").await?;
+        assert_eq!(updated.id.as_ref(), "49a3999c");
+
+        let requests = server.requests();
+        assert_eq!(requests[0].method, "PATCH");
+        assert_eq!(requests[0].path, "/emails/49a3999c");
+        assert_eq!(requests[0].body_str(), r#"{"scheduled_at":"in 1 hour"}"#);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[cfg(not(feature = "blocking"))]
+    async fn with_base_url() -> Result<()> {
+        let default = MockServer::start(|_| MockResponse::json(200, r#"{"id":"default"}"#));
+        let other = MockServer::start(|_| MockResponse::json(200, r#"{"id":"other"}"#));
+
+        let resend = Resend::builder("re_123").base_url(default.url()).build()?;
+        let email = CreateEmailBaseOptions::new("from@a.dev", ["to@a.dev"], "Subject");
+
+        let scoped = resend.emails.with_base_url(other.url());
+        assert_eq!(scoped.send(email.clone()).await?.id.as_ref(), "other");
+        assert_eq!(resend.emails.send(email).await?.id.as_ref(), "default");
+
+        assert_eq!(default.requests().len(), 1);
+        assert_eq!(other.requests().len(), 1);
+
+        Ok(())
+    }
+
+    #[maybe_async::test(feature = "blocking", async(not(feature = "blocking"), tokio::test))]
+    async fn trait_object() -> Result<()> {
+        let emails: &dyn SendEmails = &FakeEmails;
+
+        let email = CreateEmailBaseOptions::new("from@a.dev", ["to@a.dev"], "Subject");
+        let batch = vec![email.clone(), email.clone()];
+
+        let sent = emails.send(email).await?;
+        let mut ids = vec![sent.id.to_string()];
+        for sent in emails.send_batch(batch).await? {
+            ids.push(sent.id.to_string());
+        }
+        assert_eq!(ids, ["fake", "fake-0", "fake-1"]);
+
+        let result = emails.get("fake").await;
+        assert!(result.is_err_and(|e| e.is_not_found()));
+
+        Ok(())
+    }
 
     #[tokio::test]
     #[cfg(not(feature = "blocking"))]
@@ -380,7 +2845,7 @@ mod test {
         // Create
         let email = CreateEmailBaseOptions::new(from, to, subject)
             .with_text("Hello World!")
-            .with_attachment("Hello World as file.".as_bytes())
+            .with_attachment(b"Hello World as file.".as_slice())
             .with_tag(Tag::new("category", "confirm_email"));
 
         let email = resend.emails.send(email).await?;
@@ -1,5 +1,7 @@
 pub mod types {
-    use serde::Deserialize;
+    use serde::{Deserialize, Deserializer};
+
+    use crate::types::RateLimitInfo;
 
     /// Error returned as a response.
     ///
@@ -9,8 +11,35 @@ pub mod types {
     pub struct ErrorResponse {
         #[serde(rename = "statusCode")]
         pub status_code: u16,
+        /// Messages sent as an array are joined with `; `.
+        #[serde(deserialize_with = "string_or_array")]
         pub message: String,
         pub name: String,
+        /// Identifier of the failed request, see [`ResponseMetadata::request_id`].
+        ///
+        /// [`ResponseMetadata::request_id`]: crate::types::ResponseMetadata::request_id
+        #[serde(skip)]
+        pub request_id: Option<String>,
+        /// Rate limit headers of the failed response, see [`ResponseMetadata::rate_limit`].
+        ///
+        /// [`ResponseMetadata::rate_limit`]: crate::types::ResponseMetadata::rate_limit
+        #[serde(skip)]
+        pub rate_limit: Option<Box<RateLimitInfo>>,
+    }
+
+    /// Deserializes either a string or an array of strings, joined with `; `.
+    fn string_or_array<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Message {
+            One(String),
+            Many(Vec<String>),
+        }
+
+        Ok(match Message::deserialize(deserializer)? {
+            Message::One(message) => message,
+            Message::Many(messages) => messages.join("; "),
+        })
     }
 
     impl ErrorResponse {
@@ -21,6 +50,91 @@ pub mod types {
         }
     }
 
+    /// Error returned when a request fails client-side validation, before anything is sent.
+    #[non_exhaustive]
+    #[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+    pub enum ValidationError {
+        /// A required field is empty.
+        #[error("missing required field `{0}`")]
+        MissingField(&'static str),
+        /// An email doesn't have any (non-blank) `to` recipient.
+        #[error("email has no recipients")]
+        NoRecipients,
+        /// An email address couldn't be parsed.
+        #[error("invalid email address `{0}`")]
+        InvalidAddress(String),
+        /// Two tags share the same name.
+        #[error("duplicate tag `{0}`")]
+        DuplicateTag(String),
+        /// The name or value of a tag contains other characters than ASCII letters, numbers,
+        /// underscores and dashes, or is empty or longer than 256 characters.
+        #[error("invalid name or value of tag `{0}`")]
+        InvalidTag(String),
+        /// A `cc` or `bcc` address is also in `to`.
+        #[error("duplicate recipient `{0}`")]
+        DuplicateRecipient(String),
+        /// An environment variable configuring the client has an invalid value.
+        #[error("env variable `{name}` has an invalid value `{value}`")]
+        InvalidEnvVar {
+            /// Name of the environment variable.
+            name: &'static str,
+            /// Its value.
+            value: String,
+        },
+        /// An API key name is longer than [`MAX_API_KEY_NAME_LENGTH`].
+        ///
+        /// [`MAX_API_KEY_NAME_LENGTH`]: crate::limits::MAX_API_KEY_NAME_LENGTH
+        #[error("API key name of {length} characters exceeds the maximum of {max}")]
+        NameTooLong {
+            /// Amount of characters in the name.
+            length: usize,
+            /// Maximum amount of characters.
+            max: usize,
+        },
+        /// An API key restricted to a domain doesn't have `sending_access`.
+        #[error("restricting an API key to a domain requires `sending_access`")]
+        DomainRequiresSendingAccess,
+        /// An email has more recipients than [`MAX_RECIPIENTS`].
+        ///
+        /// [`MAX_RECIPIENTS`]: crate::limits::MAX_RECIPIENTS
+        #[error("{count} recipients exceed the maximum of {max}")]
+        TooManyRecipients {
+            /// Amount of `to` recipients.
+            count: usize,
+            /// Maximum amount of recipients.
+            max: usize,
+        },
+        /// The attachments of an email are larger than [`MAX_ATTACHMENT_BYTES`].
+        ///
+        /// [`MAX_ATTACHMENT_BYTES`]: crate::limits::MAX_ATTACHMENT_BYTES
+        #[error("attachments of {size} bytes exceed the maximum of {max} bytes")]
+        AttachmentsTooLarge {
+            /// Total size of the attachments' content, in bytes.
+            size: usize,
+            /// Maximum size, in bytes.
+            max: usize,
+        },
+        /// The serialized request body is larger than the configured maximum.
+        #[error("request body of {size} bytes exceeds the maximum of {max} bytes")]
+        BodyTooLarge {
+            /// Size of the serialized body, in bytes.
+            size: usize,
+            /// Configured maximum, in bytes.
+            max: usize,
+        },
+    }
+
+    /// Error returned when an id is not a valid UUID.
+    #[cfg(feature = "uuid")]
+    #[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+    #[error("`{id}` is not a valid UUID")]
+    pub struct IdParseError {
+        /// The rejected id.
+        pub id: String,
+        #[source]
+        pub(crate) source: uuid::Error,
+    }
+
     /// Error type for operations of a [`Resend`] client.
     ///
     /// <https://resend.com/docs/api-reference/errors>
@@ -170,3 +284,23 @@ pub mod types {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::types::ErrorResponse;
+
+    #[test]
+    fn message_string_or_array() -> serde_json::Result<()> {
+        let error = serde_json::from_str::<ErrorResponse>(
+            r#"{"statusCode":422,"message":"Invalid `to` field.","name":"validation_error"}"#,
+        )?;
+        assert_eq!(error.message, "Invalid `to` field.");
+
+        let error = serde_json::from_str::<ErrorResponse>(
+            r#"{"statusCode":422,"message":["Invalid `to` field.","Invalid `from` field."],"name":"validation_error"}"#,
+        )?;
+        assert_eq!(error.message, "Invalid `to` field.; Invalid `from` field.");
+
+        Ok(())
+    }
+}
@@ -25,18 +25,27 @@
 //!
 //! ```
 
+pub use builder::ResendBuilder;
 pub use client::Resend;
 pub(crate) use config::Config;
 
 mod api_keys;
 mod audiences;
 mod batch;
+mod builder;
+#[cfg(feature = "circuit-breaker")]
+mod circuit_breaker;
 mod client;
 mod config;
 mod contacts;
 mod domains;
 mod emails;
 mod error;
+mod list;
+mod metadata;
+mod rate_limit;
+#[cfg(feature = "test-util")]
+pub mod test_util;
 
 pub mod services {
     //! `Resend` API services.
@@ -46,32 +55,50 @@ pub mod services {
     pub use super::batch::BatchSvc;
     pub use super::contacts::ContactsSvc;
     pub use super::domains::DomainsSvc;
-    pub use super::emails::EmailsSvc;
+    pub use super::emails::{EmailsSvc, SendEmails};
+}
+
+pub mod limits {
+    //! Limits of the `Resend` API, checked before sending.
+
+    pub use super::api_keys::MAX_API_KEY_NAME_LENGTH;
+    pub use super::batch::MAX_BATCH_SIZE;
+    pub use super::emails::{MAX_ATTACHMENT_BYTES, MAX_RECIPIENTS};
 }
 
 pub mod types {
     //! Request and response types.
 
     pub use super::api_keys::types::{
-        ApiKey, ApiKeyId, ApiKeyToken, CreateApiKeyOptions, Permission,
+        ApiKey, ApiKeyId, ApiKeyToken, CreateApiKeyOptions, Permission, RotateResult,
     };
-    pub use super::audiences::types::{Audience, AudienceId, CreateAudienceResponse};
+    pub use super::audiences::types::{Audience, AudienceId};
     pub use super::batch::BatchSvc;
-    pub use super::contacts::types::{Contact, ContactChanges, ContactData, ContactId};
+    pub use super::config::SecretString;
+    pub use super::contacts::types::{
+        Contact, ContactChanges, ContactData, ContactId, ListContactsOptions,
+    };
     pub use super::domains::types::{
         CreateDomainOptions, DkimRecordType, Domain, DomainChanges, DomainDkimRecord, DomainId,
         DomainRecord, DomainSpfRecord, DomainStatus, ProxyStatus, Region, SpfRecordType, Tls,
-        UpdateDomainResponse,
+        UpdateDomainResponse, VerifyDomainResponse,
     };
     pub use super::emails::types::{
-        Attachment, ContentOrPath, CreateEmailBaseOptions, CreateEmailResponse, Email, EmailId, Tag,
+        Attachment, ContentOrPath, CreateEmailBaseOptions, CreateEmailResponse, Disposition, Email,
+        EmailAddress, EmailEvent, EmailId, Tag,
     };
-    pub use super::error::types::{ErrorKind, ErrorResponse};
+    #[cfg(feature = "uuid")]
+    pub use super::error::types::IdParseError;
+    pub use super::error::types::{ErrorKind, ErrorResponse, ValidationError};
+    pub use super::list::types::{Cursor, Page};
+    pub use super::metadata::types::ResponseMetadata;
+    pub use super::rate_limit::types::{RateLimitHandle, RateLimitInfo};
 }
 
 /// Error type for operations of a [`Resend`] client.
 ///
 /// <https://resend.com/docs/api-reference/errors>
+#[non_exhaustive]
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
     /// Errors that may occur during the processing an HTTP request.
@@ -82,9 +109,96 @@ pub enum Error {
     #[error("resend error: {0}")]
     Resend(#[from] types::ErrorResponse),
 
+    /// Errors that may occur during the client-side validation of a request.
+    #[error("validation error: {0}")]
+    Validation(#[from] types::ValidationError),
+
     /// Errors that may occur during the parsing of an API response.
     #[error("Failed to parse Resend API response. Received: \n{0}")]
     Parse(String),
+
+    /// A response was JSON but didn't match the expected type.
+    #[error("failed to decode Resend API response: {error}. Received: \n{body}")]
+    Decode {
+        /// Why decoding failed.
+        #[source]
+        error: serde_json::Error,
+        /// The whole response body.
+        body: String,
+    },
+
+    /// A successful response wasn't JSON, usually an HTML page returned by a proxy in front of
+    /// the API.
+    #[error("expected a JSON response, received `{content_type}`: {body_snippet}")]
+    UnexpectedContentType {
+        /// The `Content-Type` header of the response.
+        content_type: String,
+        /// The start of the response body.
+        body_snippet: String,
+    },
+
+    /// A batch request failed, every email of the batch gets the same error, see
+    /// [`BatchSvc::send_indexed`].
+    ///
+    /// [`BatchSvc::send_indexed`]: crate::services::BatchSvc::send_indexed
+    #[error("batch request failed: {0}")]
+    Batch(std::sync::Arc<Self>),
+
+    /// The request wasn't sent because too many requests failed in a row, see
+    /// [`ResendBuilder::circuit_breaker`].
+    #[cfg(feature = "circuit-breaker")]
+    #[error("circuit breaker is open after repeated failures")]
+    CircuitOpen,
+}
+
+impl Error {
+    /// Returns whether the request may succeed if retried as is.
+    ///
+    /// This is the case for rate limits (`429`), server errors (`5xx`), timeouts, connection
+    /// failures and non-JSON error pages (usually returned by a proxy in front of the API).
+    #[must_use]
+    pub fn is_retriable(&self) -> bool {
+        match self {
+            Self::Http(error) => {
+                error.is_timeout()
+                    || error.is_connect()
+                    || error
+                        .status()
+                        .is_some_and(|status| status.as_u16() == 429 || status.is_server_error())
+            }
+            Self::Resend(error) => error.status_code == 429 || error.status_code >= 500,
+            Self::Validation(_) | Self::Decode { .. } => false,
+            Self::Parse(_) | Self::UnexpectedContentType { .. } => true,
+            Self::Batch(error) => error.is_retriable(),
+            #[cfg(feature = "circuit-breaker")]
+            Self::CircuitOpen => true,
+        }
+    }
+    /// Returns whether the requested resource doesn't exist (`404`).
+    #[must_use]
+    pub fn is_not_found(&self) -> bool {
+        match self {
+            Self::Http(error) => error.status().is_some_and(|status| status.as_u16() == 404),
+            Self::Resend(error) => error.status_code == 404,
+            Self::Validation(_)
+            | Self::Parse(_)
+            | Self::Decode { .. }
+            | Self::UnexpectedContentType { .. } => false,
+            Self::Batch(error) => error.is_not_found(),
+            #[cfg(feature = "circuit-breaker")]
+            Self::CircuitOpen => false,
+        }
+    }
+
+    /// Returns the underlying [`reqwest::Error`] of an [`Error::Http`], to use its predicates
+    /// such as [`reqwest::Error::is_timeout`].
+    #[must_use]
+    pub const fn as_reqwest(&self) -> Option<&reqwest::Error> {
+        match self {
+            Self::Http(error) => Some(error),
+            _ => None,
+        }
+    }
 }
 
 /// Specialized [`Result`] type for an [`Error`].
@@ -94,10 +208,12 @@ pub type Result<T, E = Error> = std::result::Result<T, E>;
 
 #[cfg(test)]
 pub(crate) mod tests {
-    use std::sync::OnceLock;
+    use std::sync::{Mutex, OnceLock};
 
     use crate::Resend;
 
+    pub mod mock;
+
     /// Use this client in all tests to ensure rate limits are respected.
     ///
     /// Instantiate with:
@@ -105,4 +221,94 @@ pub(crate) mod tests {
     /// let resend = CLIENT.get_or_init(Resend::default);
     /// ```
     pub static CLIENT: OnceLock<Resend> = OnceLock::new();
+
+    /// Held by tests that set environment variables, so that they don't overwrite each other's.
+    ///
+    /// Unset the variables again before releasing it, other tests read them too.
+    pub static ENV: Mutex<()> = Mutex::new(());
+
+    #[tokio::test]
+    #[cfg(not(feature = "blocking"))]
+    async fn is_retriable() -> crate::Result<()> {
+        use std::time::Duration;
+
+        use mock::{MockResponse, MockServer};
+
+        use crate::types::CreateEmailBaseOptions;
+
+        let email = CreateEmailBaseOptions::new("from@a.dev", ["to@a.dev"], "Subject");
+        let error = |status: u16| {
+            let body = format!(r#"{{"statusCode":{status},"message":"","name":"error"}}"#);
+            MockResponse::json(status, &body)
+        };
+
+        let server = MockServer::start(move |_| error(503));
+        let resend = Resend::builder("re_123").base_url(server.url()).build()?;
+        let result = resend.emails.send(email.clone()).await;
+        assert!(result.is_err_and(|e| e.is_retriable()));
+
+        let server = MockServer::start(move |_| error(422));
+        let resend = Resend::builder("re_123").base_url(server.url()).build()?;
+        let result = resend.emails.send(email.clone()).await;
+        assert!(result.is_err_and(|e| !e.is_retriable()));
+
+        let server = MockServer::start(|_| {
+            std::thread::sleep(Duration::from_millis(500));
+            MockResponse::json(200, r#"{"id":"1"}"#)
+        });
+        let resend = Resend::builder("re_123")
+            .base_url(server.url())
+            .timeout(Duration::from_millis(50))
+            .build()?;
+        let result = resend.emails.send(email).await;
+        assert!(result.is_err_and(|e| e.is_retriable()));
+
+        Ok(())
+    }
+    #[maybe_async::test(feature = "blocking", async(not(feature = "blocking"), tokio::test))]
+    async fn decode_error() -> crate::Result<()> {
+        use mock::{MockResponse, MockServer};
+
+        use crate::Error;
+
+        let server = MockServer::start(|_| MockResponse::json(200, r#"{"identifier":"1"}"#));
+        let resend = Resend::builder("re_123").base_url(server.url()).build()?;
+
+        // Same error with and without the `blocking` feature.
+        let result = resend.emails.get("49a3999c").await;
+        assert!(matches!(
+            result,
+            Err(Error::Decode { body, .. }) if body == r#"{"identifier":"1"}"#
+        ));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[cfg(not(feature = "blocking"))]
+    async fn as_reqwest() -> crate::Result<()> {
+        use std::time::Duration;
+
+        use mock::{MockResponse, MockServer};
+
+        use crate::types::CreateEmailBaseOptions;
+
+        let server = MockServer::start(|_| {
+            std::thread::sleep(Duration::from_millis(500));
+            MockResponse::json(200, r#"{"id":"1"}"#)
+        });
+        let resend = Resend::builder("re_123")
+            .base_url(server.url())
+            .timeout(Duration::from_millis(50))
+            .build()?;
+
+        let email = CreateEmailBaseOptions::new("from@a.dev", ["to@a.dev"], "Subject");
+        let result = resend.emails.send(email).await;
+        assert!(result.is_err_and(|e| e.as_reqwest().is_some_and(reqwest::Error::is_timeout)));
+
+        let error = crate::Error::Parse(String::new());
+        assert!(error.as_reqwest().is_none());
+
+        Ok(())
+    }
 }
@@ -0,0 +1,245 @@
+use self::types::{Cursor, Page};
+
+impl<T> Page<T> {
+    /// Sets [`Page::next_cursor`] to the id of the last item if there are more pages.
+    pub(crate) fn with_next_cursor(mut self, id: impl Fn(&T) -> &str) -> Self {
+        self.next_cursor = self
+            .has_more
+            .then(|| self.data.last().map(|item| Cursor::new(id(item))))
+            .flatten();
+        self
+    }
+}
+
+pub mod types {
+    use std::{convert::Infallible, fmt, ops::Deref, str::FromStr};
+
+    use ecow::EcoString;
+    use serde::{de, Deserialize, Deserializer};
+
+    /// Opaque position in a paginated list, as returned in [`Page::next_cursor`].
+    ///
+    /// Pass it as the `after` (or `before`) option of the next list call to continue from there.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct Cursor(EcoString);
+
+    impl Cursor {
+        /// Creates a new [`Cursor`], e.g. from one persisted between runs.
+        #[inline]
+        #[must_use]
+        pub fn new(cursor: &str) -> Self {
+            Self(EcoString::from(cursor))
+        }
+    }
+
+    impl Deref for Cursor {
+        type Target = str;
+
+        fn deref(&self) -> &Self::Target {
+            self.as_ref()
+        }
+    }
+
+    impl AsRef<str> for Cursor {
+        #[inline]
+        fn as_ref(&self) -> &str {
+            self.0.as_str()
+        }
+    }
+
+    impl FromStr for Cursor {
+        type Err = Infallible;
+
+        /// Same as [`Cursor::new`], never fails.
+        #[inline]
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            Ok(Self::new(s))
+        }
+    }
+
+    impl fmt::Display for Cursor {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            fmt::Display::fmt(self.as_ref(), f)
+        }
+    }
+
+    /// A single page of a paginated list endpoint.
+    ///
+    /// Deserializing fails if the response is not a `list` object or has no `data`.
+    #[must_use]
+    #[derive(Debug, Clone)]
+    pub struct Page<T> {
+        /// Items of this page.
+        pub data: Vec<T>,
+        /// Whether there are more items after this page.
+        pub has_more: bool,
+        /// Cursor pointing past the last item of this page, `None` on the last page.
+        pub next_cursor: Option<Cursor>,
+    }
+
+    impl<T> Page<T> {
+        /// Returns an iterator over the items of this page.
+        #[inline]
+        pub fn iter(&self) -> std::slice::Iter<'_, T> {
+            self.data.iter()
+        }
+
+        /// Returns the number of items in this page.
+        #[inline]
+        #[must_use]
+        pub const fn len(&self) -> usize {
+            self.data.len()
+        }
+
+        /// Returns `true` if this page has no items.
+        #[inline]
+        #[must_use]
+        pub const fn is_empty(&self) -> bool {
+            self.data.is_empty()
+        }
+    }
+
+    impl<'a, T> IntoIterator for &'a Page<T> {
+        type Item = &'a T;
+        type IntoIter = std::slice::Iter<'a, T>;
+
+        fn into_iter(self) -> Self::IntoIter {
+            self.iter()
+        }
+    }
+
+    impl<T> IntoIterator for Page<T> {
+        type Item = T;
+        type IntoIter = std::vec::IntoIter<T>;
+
+        fn into_iter(self) -> Self::IntoIter {
+            self.data.into_iter()
+        }
+    }
+
+    /// Wire format of a [`Page`], before validation.
+    #[derive(Deserialize)]
+    struct RawPage<T> {
+        object: Option<String>,
+        data: Option<Vec<T>>,
+        #[serde(default)]
+        has_more: bool,
+    }
+
+    impl<'de, T: Deserialize<'de>> Deserialize<'de> for Page<T> {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let raw = RawPage::<T>::deserialize(deserializer)?;
+
+            if let Some(object) = raw.object.filter(|object| object != "list") {
+                let message = format!("expected a `list` response, got a(n) `{object}`");
+                return Err(de::Error::custom(message));
+            }
+            let data = raw
+                .data
+                .ok_or_else(|| de::Error::custom("`list` response is missing its `data`"))?;
+
+            Ok(Self {
+                data,
+                has_more: raw.has_more,
+                next_cursor: None,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::types::{Contact, Page};
+
+    #[test]
+    fn contacts_page() -> serde_json::Result<()> {
+        let response = r#"{
+            "object": "list",
+            "has_more": true,
+            "data": [
+                {
+                    "id": "e169aa45-1ecf-4183-9955-b1499d5701d3",
+                    "email": "steve.wozniak@gmail.com",
+                    "first_name": "Steve",
+                    "last_name": "Wozniak",
+                    "created_at": "2023-10-06T23:47:56.678Z",
+                    "unsubscribed": false
+                }
+            ]
+        }"#;
+
+        let page = serde_json::from_str::<Page<Contact>>(response)?;
+        assert_eq!(page.data.len(), 1);
+        assert!(page.has_more);
+        assert!(page.next_cursor.is_none());
+
+        let page = page.with_next_cursor(|contact| &contact.id);
+        assert_eq!(
+            page.next_cursor.as_deref(),
+            Some("e169aa45-1ecf-4183-9955-b1499d5701d3")
+        );
+
+        let page = serde_json::from_str::<Page<Contact>>(r#"{"object":"list","data":[]}"#)?;
+        assert!(!page.has_more);
+        assert!(page
+            .with_next_cursor(|contact| &contact.id)
+            .next_cursor
+            .is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn iterate_page() -> serde_json::Result<()> {
+        let response = r#"{
+            "object": "list",
+            "data": [
+                {
+                    "id": "1",
+                    "email": "a@a.dev",
+                    "first_name": "A",
+                    "last_name": "A",
+                    "created_at": "2023-10-06T23:47:56.678Z",
+                    "unsubscribed": false
+                },
+                {
+                    "id": "2",
+                    "email": "b@a.dev",
+                    "first_name": "B",
+                    "last_name": "B",
+                    "created_at": "2023-10-06T23:47:56.678Z",
+                    "unsubscribed": true
+                }
+            ]
+        }"#;
+        let page = serde_json::from_str::<Page<Contact>>(response)?;
+
+        assert_eq!(page.len(), 2);
+        assert!(!page.is_empty());
+        let mut emails = Vec::new();
+        for contact in &page {
+            emails.push(contact.email.as_str());
+        }
+        assert_eq!(emails, ["a@a.dev", "b@a.dev"]);
+        assert_eq!(
+            page.iter().filter(|contact| contact.unsubscribed).count(),
+            1
+        );
+
+        let page = serde_json::from_str::<Page<Contact>>(r#"{"object":"list","data":[]}"#)?;
+        assert_eq!(page.len(), 0);
+        assert!(page.is_empty());
+        assert!(page.into_iter().next().is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_list() {
+        let result = serde_json::from_str::<Page<Contact>>(r#"{"object":"list"}"#);
+        assert!(result.is_err_and(|e| e.to_string().contains("missing its `data`")));
+
+        let result = serde_json::from_str::<Page<Contact>>(r#"{"object":"contact","data":[]}"#);
+        assert!(result.is_err_and(|e| e.to_string().contains("got a(n) `contact`")));
+    }
+}
@@ -0,0 +1,103 @@
+use reqwest::header::{HeaderMap, CONTENT_LENGTH};
+
+use self::types::ResponseMetadata;
+use crate::types::RateLimitInfo;
+
+impl ResponseMetadata {
+    /// Collects the metadata carried by the headers of a response to a request of
+    /// `request_bytes`.
+    pub(crate) fn from_headers(headers: &HeaderMap, request_bytes: usize) -> Self {
+        let request_id = headers
+            .get("x-request-id")
+            .and_then(|value| value.to_str().ok())
+            .map(ToOwned::to_owned);
+        let response_bytes = headers
+            .get(CONTENT_LENGTH)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse().ok());
+
+        Self {
+            request_id,
+            rate_limit: RateLimitInfo::from_headers(headers),
+            request_bytes,
+            response_bytes,
+        }
+    }
+}
+
+pub mod types {
+    use crate::types::RateLimitInfo;
+
+    /// Metadata of a response, independent of its body.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct ResponseMetadata {
+        /// Identifier of the request (`x-request-id`), include it when contacting Resend support.
+        pub request_id: Option<String>,
+        /// Rate limit state reported by the response.
+        pub rate_limit: Option<RateLimitInfo>,
+        /// Size of the request body, in bytes.
+        pub request_bytes: usize,
+        /// Size of the response body from its `Content-Length`, in bytes.
+        ///
+        /// `None` if the response doesn't report it, e.g. when it is chunked.
+        pub response_bytes: Option<u64>,
+    }
+}
+
+#[cfg(all(test, not(feature = "blocking")))]
+mod test {
+    use crate::tests::mock::{MockResponse, MockServer};
+    use crate::types::CreateEmailBaseOptions;
+    use crate::{Error, Resend, Result};
+
+    #[tokio::test]
+    async fn request_id() -> Result<()> {
+        let server = MockServer::start(|request| {
+            if request.body_str().contains("fail") {
+                let body = r#"{"statusCode":422,"message":"Invalid `to` field.","name":"validation_error"}"#;
+                MockResponse::json(422, body).with_header("x-request-id", "req_2")
+            } else {
+                MockResponse::json(200, r#"{"id":"1"}"#).with_header("x-request-id", "req_1")
+            }
+        });
+        let resend = Resend::builder("re_123").base_url(server.url()).build()?;
+        assert!(resend.last_response_metadata().is_none());
+
+        let email = CreateEmailBaseOptions::new("from@a.dev", ["to@a.dev"], "Subject");
+        let _ = resend.emails.send(email).await?;
+        let metadata = resend.last_response_metadata();
+        assert_eq!(
+            metadata.and_then(|m| m.request_id).as_deref(),
+            Some("req_1")
+        );
+
+        let email = CreateEmailBaseOptions::new("from@a.dev", ["to@a.dev"], "fail");
+        let result = resend.emails.send(email).await;
+        assert!(matches!(
+            result,
+            Err(Error::Resend(error)) if error.request_id.as_deref() == Some("req_2")
+        ));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn byte_counts() -> Result<()> {
+        let server = MockServer::start(|_| MockResponse::json(200, r#"{"id":"1"}"#));
+        let resend = Resend::builder("re_123").base_url(server.url()).build()?;
+
+        let email = CreateEmailBaseOptions::new("from@a.dev", ["to@a.dev"], "Subject");
+        let _ = resend.emails.send(email).await?;
+
+        let metadata = resend.last_response_metadata();
+        let request_bytes = server.requests()[0].body.len();
+        assert!(request_bytes > 0);
+        assert_eq!(
+            metadata.as_ref().map(|m| m.request_bytes),
+            Some(request_bytes)
+        );
+        assert_eq!(metadata.and_then(|m| m.response_bytes), Some(10));
+
+        Ok(())
+    }
+}
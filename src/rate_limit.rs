@@ -0,0 +1,447 @@
+#[cfg(not(feature = "blocking"))]
+use std::future::Future;
+use std::num::NonZeroU32;
+#[cfg(feature = "blocking")]
+use std::sync::Condvar;
+use std::sync::{Arc, Mutex, PoisonError};
+#[cfg(not(feature = "blocking"))]
+use std::task::{Context, Poll, Waker};
+use std::time::Duration;
+
+#[cfg(feature = "blocking")]
+use governor::clock::Clock;
+use governor::{clock::QuantaClock, Quota, RateLimiter};
+use reqwest::header::HeaderMap;
+
+use self::types::{RateLimitHandle, RateLimitInfo};
+
+impl RateLimitHandle {
+    /// Creates a new [`RateLimitHandle`] allowing `quota`.
+    pub(crate) fn new(quota: Quota) -> Self {
+        let clock = QuantaClock::default();
+
+        Self {
+            limiter: Arc::new(RateLimiter::direct_with_clock(quota, &clock)),
+            #[cfg(feature = "blocking")]
+            clock,
+        }
+    }
+
+    /// Creates a new [`RateLimitHandle`] that never waits.
+    pub(crate) fn unlimited() -> Self {
+        Self::new(Quota::per_second(NonZeroU32::MAX))
+    }
+
+    /// Waits until a request can be made without exceeding the quota, and consumes it.
+    ///
+    /// Cancellation safe: the quota is only consumed once the wait is over, dropping the future
+    /// while it waits (e.g. in a `tokio::select!`) leaves the quota untouched.
+    #[maybe_async::maybe_async]
+    pub async fn acquire(&self) {
+        #[cfg(not(feature = "blocking"))]
+        {
+            let jitter =
+                governor::Jitter::new(Duration::from_millis(10), Duration::from_millis(50));
+            self.limiter.until_ready_with_jitter(jitter).await;
+        }
+
+        #[cfg(feature = "blocking")]
+        while let Err(not_until) = self.limiter.check() {
+            std::thread::sleep(not_until.wait_time_from(self.clock.now()));
+        }
+    }
+}
+
+/// Concurrency limit that halves on every `429` and grows back by one on every success.
+pub struct AdaptiveConcurrency {
+    max: usize,
+    /// Requests in flight and the current limit.
+    state: Mutex<(usize, usize)>,
+    #[cfg(feature = "blocking")]
+    released: Condvar,
+    /// Tasks waiting for a request to finish or for the limit to grow.
+    #[cfg(not(feature = "blocking"))]
+    waiting: Mutex<Vec<Waker>>,
+}
+
+impl AdaptiveConcurrency {
+    /// Creates a new [`AdaptiveConcurrency`], starting at `max`.
+    pub fn new(max: usize) -> Self {
+        let max = max.max(1);
+
+        Self {
+            max,
+            state: Mutex::new((0, max)),
+            #[cfg(feature = "blocking")]
+            released: Condvar::new(),
+            #[cfg(not(feature = "blocking"))]
+            waiting: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Blocks until fewer requests than the current limit are in flight, then runs `request`.
+    #[cfg(feature = "blocking")]
+    pub fn run<T>(&self, request: impl FnOnce() -> T) -> T {
+        let lock = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        let mut state = self
+            .released
+            .wait_while(lock, |(in_flight, limit)| *in_flight >= *limit)
+            .unwrap_or_else(PoisonError::into_inner);
+        state.0 += 1;
+        drop(state);
+
+        let result = request();
+
+        self.state.lock().unwrap_or_else(PoisonError::into_inner).0 -= 1;
+        self.released.notify_all();
+
+        result
+    }
+
+    /// Waits until fewer requests than the current limit are in flight, then runs `request`.
+    #[cfg(not(feature = "blocking"))]
+    pub async fn run<F: Future>(&self, request: impl FnOnce() -> F) -> F::Output {
+        std::future::poll_fn(|cx| self.poll_acquire(cx)).await;
+
+        let result = request().await;
+
+        self.state.lock().unwrap_or_else(PoisonError::into_inner).0 -= 1;
+        self.wake_all();
+
+        result
+    }
+
+    /// Takes a slot if fewer requests than the current limit are in flight, or else waits to be
+    /// woken up by [`AdaptiveConcurrency::wake_all`].
+    #[cfg(not(feature = "blocking"))]
+    fn poll_acquire(&self, cx: &Context<'_>) -> Poll<()> {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        if state.0 < state.1 {
+            state.0 += 1;
+            return Poll::Ready(());
+        }
+
+        // Registered while holding the state, so that a release can't slip in between.
+        self.waiting
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(cx.waker().clone());
+        drop(state);
+
+        Poll::Pending
+    }
+
+    #[cfg(not(feature = "blocking"))]
+    fn wake_all(&self) {
+        let waiting =
+            std::mem::take(&mut *self.waiting.lock().unwrap_or_else(PoisonError::into_inner));
+        for waker in waiting {
+            waker.wake();
+        }
+    }
+
+    /// Halves the limit after a `429`, or grows it by one after a success.
+    pub fn adapt(&self, rate_limited: bool) {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        state.1 = if rate_limited {
+            (state.1 / 2).max(1)
+        } else {
+            (state.1 + 1).min(self.max)
+        };
+        drop(state);
+
+        #[cfg(feature = "blocking")]
+        self.released.notify_all();
+        #[cfg(not(feature = "blocking"))]
+        self.wake_all();
+    }
+}
+
+impl RateLimitInfo {
+    /// Parses the rate limit headers of a response.
+    ///
+    /// Returns `None` if the response doesn't carry any of them.
+    pub(crate) fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let parse = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.trim().parse::<u64>().ok())
+        };
+
+        let info = Self {
+            limit: parse("ratelimit-limit"),
+            remaining: parse("ratelimit-remaining"),
+            reset: parse("ratelimit-reset").map(Duration::from_secs),
+            retry_after: parse("retry-after").map(Duration::from_secs),
+        };
+
+        let empty = info.limit.is_none()
+            && info.remaining.is_none()
+            && info.reset.is_none()
+            && info.retry_after.is_none();
+
+        (!empty).then_some(info)
+    }
+}
+
+pub mod types {
+    use std::fmt;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use governor::{
+        clock::{QuantaClock, QuantaInstant},
+        middleware::NoOpMiddleware,
+        state::{InMemoryState, NotKeyed},
+        RateLimiter,
+    };
+
+    type Limiter = RateLimiter<NotKeyed, InMemoryState, QuantaClock, NoOpMiddleware<QuantaInstant>>;
+
+    /// Handle to the rate limiter of a [`Resend`] client, configured by `RESEND_RATE_LIMIT`.
+    ///
+    /// Clones share the same quota, use it to gate requests made to the `Resend` API outside of
+    /// this crate so that they count towards the same limit.
+    ///
+    /// [`Resend`]: crate::Resend
+    #[derive(Clone)]
+    pub struct RateLimitHandle {
+        pub(crate) limiter: Arc<Limiter>,
+        #[cfg(feature = "blocking")]
+        pub(crate) clock: QuantaClock,
+    }
+
+    impl fmt::Debug for RateLimitHandle {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_struct("RateLimitHandle").finish_non_exhaustive()
+        }
+    }
+
+    /// Rate limit state reported by the `Resend` API through response headers.
+    ///
+    /// <https://resend.com/docs/api-reference/introduction#rate-limit>
+    #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+    pub struct RateLimitInfo {
+        /// The maximum number of requests allowed within the time window (`ratelimit-limit`).
+        pub limit: Option<u64>,
+        /// How many requests are left within the current window (`ratelimit-remaining`).
+        pub remaining: Option<u64>,
+        /// How long until the window resets (`ratelimit-reset`).
+        pub reset: Option<Duration>,
+        /// How long to wait before retrying, usually only sent with a 429 (`retry-after`).
+        pub retry_after: Option<Duration>,
+    }
+}
+
+#[cfg(all(test, not(feature = "blocking")))]
+mod test {
+    use std::time::Duration;
+
+    use crate::tests::mock::{MockResponse, MockServer};
+    use crate::types::{CreateEmailBaseOptions, RateLimitInfo};
+    use crate::{Resend, Result};
+
+    #[tokio::test]
+    async fn captures_rate_limit_headers() -> Result<()> {
+        let server = MockServer::start(|_| {
+            MockResponse::json(200, r#"{"id":"1"}"#)
+                .with_header("ratelimit-limit", "10")
+                .with_header("ratelimit-remaining", "7")
+                .with_header("ratelimit-reset", "1")
+        });
+        let resend = Resend::builder("re_123").base_url(server.url()).build()?;
+        assert!(resend.last_rate_limit_info().is_none());
+
+        let email = CreateEmailBaseOptions::new("from@a.dev", ["to@a.dev"], "Subject");
+        let _ = resend.emails.send(email).await?;
+
+        let expected = RateLimitInfo {
+            limit: Some(10),
+            remaining: Some(7),
+            reset: Some(Duration::from_secs(1)),
+            retry_after: None,
+        };
+        assert_eq!(resend.last_rate_limit_info(), Some(expected));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn shared_rate_limiter() -> Result<()> {
+        use std::time::Instant;
+
+        let server = MockServer::start(|_| MockResponse::json(200, r#"{"id":"1"}"#));
+        let resend = Resend::builder("re_123").base_url(server.url()).build()?;
+
+        // Use up the whole burst outside of the client.
+        let limiter = resend.rate_limiter();
+        for _ in 0..8 {
+            limiter.acquire().await;
+        }
+
+        let start = Instant::now();
+        let email = CreateEmailBaseOptions::new("from@a.dev", ["to@a.dev"], "Subject");
+        let _ = resend.emails.send(email).await?;
+        assert!(start.elapsed() >= Duration::from_millis(50));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn custom_client_rate_limited() -> Result<()> {
+        use std::time::Instant;
+
+        let server = MockServer::start(|_| MockResponse::json(200, r#"{"id":"1"}"#));
+        let resend = Resend::with_client("re_123", reqwest::Client::new());
+        let resend = resend.emails.with_base_url(server.url());
+
+        let email = CreateEmailBaseOptions::new("from@a.dev", ["to@a.dev"], "Subject");
+        for _ in 0..8 {
+            let _ = resend.send(email.clone()).await?;
+        }
+
+        let start = Instant::now();
+        let _ = resend.send(email).await?;
+        assert!(start.elapsed() >= Duration::from_millis(50));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn rate_limit_disabled() -> Result<()> {
+        use std::time::Instant;
+
+        let server = MockServer::start(|_| MockResponse::json(200, r#"{"id":"1"}"#));
+        let resend = Resend::builder("re_123")
+            .base_url(server.url())
+            .rate_limit(false)
+            .build()?;
+
+        let start = Instant::now();
+        let email = CreateEmailBaseOptions::new("from@a.dev", ["to@a.dev"], "Subject");
+        for _ in 0..20 {
+            let _ = resend.emails.send(email.clone()).await?;
+        }
+        assert!(start.elapsed() < Duration::from_secs(1));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn cancelled_wait_keeps_quota() -> Result<()> {
+        use std::time::Instant;
+
+        let server = MockServer::start(|_| MockResponse::json(200, r#"{"id":"1"}"#));
+        let resend = Resend::builder("re_123").base_url(server.url()).build()?;
+        let email = CreateEmailBaseOptions::new("from@a.dev", ["to@a.dev"], "Subject");
+
+        let start = Instant::now();
+        let limiter = resend.rate_limiter();
+        for _ in 0..8 {
+            limiter.acquire().await;
+        }
+
+        // Dropped while waiting on the rate limiter.
+        let cancelled =
+            tokio::time::timeout(Duration::from_millis(20), resend.emails.send(email.clone()));
+        assert!(cancelled.await.is_err());
+
+        // Neither starved nor allowed through early: the next request takes the next slot.
+        let _ = resend.emails.send(email).await?;
+        let elapsed = start.elapsed();
+        assert!(elapsed >= Duration::from_secs(1));
+        assert!(elapsed < Duration::from_secs(2));
+        assert_eq!(server.requests().len(), 1);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn clones_share_rate_limiter() -> Result<()> {
+        use std::time::Instant;
+
+        let server = MockServer::start(|_| MockResponse::json(200, r#"{"id":"1"}"#));
+        let resend = Resend::builder("re_123").base_url(server.url()).build()?;
+        let clone = resend.clone();
+
+        // Use up the whole burst through one clone.
+        let email = CreateEmailBaseOptions::new("from@a.dev", ["to@a.dev"], "Subject");
+        for _ in 0..8 {
+            let _ = resend.emails.send(email.clone()).await?;
+        }
+
+        let start = Instant::now();
+        let _ = clone.emails.send(email).await?;
+        assert!(start.elapsed() >= Duration::from_millis(50));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "tracing")]
+    async fn rate_limit_wait_span_field() -> Result<()> {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::{Arc, Mutex};
+
+        use tracing::field::{Field, Visit};
+        use tracing::span::{Attributes, Id, Record};
+        use tracing::{Event, Metadata, Subscriber};
+
+        /// Collects every recorded `rate_limit_wait_ms` and counts how often the span is entered.
+        #[derive(Clone)]
+        struct WaitRecorder(Arc<Mutex<Vec<u64>>>, Arc<AtomicUsize>);
+
+        impl Visit for WaitRecorder {
+            fn record_u64(&mut self, field: &Field, value: u64) {
+                if field.name() == "rate_limit_wait_ms" {
+                    self.0.lock().expect("not poisoned").push(value);
+                }
+            }
+
+            fn record_debug(&mut self, _: &Field, _: &dyn std::fmt::Debug) {}
+        }
+
+        impl Subscriber for WaitRecorder {
+            fn enabled(&self, _: &Metadata<'_>) -> bool {
+                true
+            }
+            fn new_span(&self, _: &Attributes<'_>) -> Id {
+                Id::from_u64(1)
+            }
+            fn record(&self, _: &Id, values: &Record<'_>) {
+                values.record(&mut self.clone());
+            }
+            fn record_follows_from(&self, _: &Id, _: &Id) {}
+            fn event(&self, _: &Event<'_>) {}
+            fn enter(&self, _: &Id) {
+                self.1.fetch_add(1, Ordering::SeqCst);
+            }
+            fn exit(&self, _: &Id) {}
+        }
+
+        let waits = Arc::new(Mutex::new(Vec::new()));
+        let enters = Arc::new(AtomicUsize::new(0));
+        let recorder = WaitRecorder(waits.clone(), enters.clone());
+        let _guard = tracing::subscriber::set_default(recorder);
+
+        let server = MockServer::start(|_| MockResponse::json(200, r#"{"id":"1"}"#));
+        let resend = Resend::builder("re_123").base_url(server.url()).build()?;
+
+        // The 9th email waits for the burst of 8 to replenish, the 9th request of the default
+        // quota is reserved for priority sends.
+        let email = CreateEmailBaseOptions::new("from@a.dev", ["to@a.dev"], "Subject");
+        for _ in 0..9 {
+            let _ = resend.emails.send(email.clone()).await?;
+        }
+
+        let waits = waits.lock().expect("not poisoned").clone();
+        assert_eq!(waits.len(), 9);
+        assert!(waits[8] > 0);
+
+        // The span is entered while the request is in flight.
+        assert!(enters.load(Ordering::SeqCst) >= 9);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,143 @@
+//! Test doubles for code built on top of this crate.
+//!
+//! Only available with the `test-util` feature.
+
+use std::sync::{Arc, Mutex, PoisonError};
+
+use crate::services::SendEmails;
+use crate::types::{CreateEmailBaseOptions, CreateEmailResponse, Email, EmailId, ErrorResponse};
+use crate::{Error, Result};
+
+/// In-memory stand-in for [`EmailsSvc`] that records every sent email.
+///
+/// Emails are validated the same way [`EmailsSvc`] validates them before sending.
+///
+/// Returned ids are deterministic: the `n`th sent email gets the id
+/// `00000000-0000-0000-0000-{n:012}`, starting at `1`. Clones share the same recorded emails.
+///
+/// [`EmailsSvc`]: crate::services::EmailsSvc
+#[must_use]
+#[derive(Debug, Default, Clone)]
+pub struct MockClient {
+    sent: Arc<Mutex<Vec<(EmailId, CreateEmailBaseOptions)>>>,
+}
+
+impl MockClient {
+    /// Creates a new [`MockClient`] with no recorded emails.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns all emails sent so far, in the order they were sent.
+    #[must_use]
+    pub fn sent(&self) -> Vec<CreateEmailBaseOptions> {
+        let sent = self.sent.lock().unwrap_or_else(PoisonError::into_inner);
+        sent.iter().map(|(_, email)| email.clone()).collect()
+    }
+
+    fn record(&self, email: CreateEmailBaseOptions) -> EmailId {
+        let mut sent = self.sent.lock().unwrap_or_else(PoisonError::into_inner);
+        let id = EmailId::new(&format!("00000000-0000-0000-0000-{:012}", sent.len() + 1));
+        sent.push((id.clone(), email));
+        id
+    }
+}
+
+#[maybe_async::maybe_async]
+impl SendEmails for MockClient {
+    async fn send(&self, email: CreateEmailBaseOptions) -> Result<CreateEmailResponse> {
+        email.validate()?;
+
+        let id = self.record(email);
+        Ok(CreateEmailResponse {
+            id,
+            warnings: Vec::new(),
+            scheduled_at: None,
+        })
+    }
+
+    async fn send_batch(
+        &self,
+        emails: Vec<CreateEmailBaseOptions>,
+    ) -> Result<Vec<CreateEmailResponse>> {
+        for email in &emails {
+            email.validate()?;
+        }
+
+        let ids = emails.into_iter().map(|email| CreateEmailResponse {
+            id: self.record(email),
+            warnings: Vec::new(),
+            scheduled_at: None,
+        });
+
+        Ok(ids.collect())
+    }
+
+    async fn get(&self, email_id: &str) -> Result<Email> {
+        let found = self
+            .sent
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .find(|(id, _)| id.as_ref() == email_id)
+            .cloned();
+
+        let (id, email) = found.ok_or_else(|| {
+            Error::Resend(ErrorResponse {
+                status_code: 404,
+                message: "Email not found".to_owned(),
+                name: "not_found".to_owned(),
+                request_id: None,
+                rate_limit: None,
+            })
+        })?;
+
+        Ok(Email {
+            id,
+            from: email.from,
+            to: email.to,
+            subject: email.subject,
+            created_at: "1970-01-01T00:00:00.000Z".to_owned(),
+            html: email.html,
+            text: email.text,
+            bcc: email.bcc.unwrap_or_default(),
+            cc: email.cc.unwrap_or_default(),
+            reply_to: email.reply_to,
+            last_event: "sent".to_owned(),
+        })
+    }
+}
+
+#[cfg(all(test, not(feature = "blocking")))]
+mod test {
+    use crate::services::SendEmails;
+    use crate::test_util::MockClient;
+    use crate::types::CreateEmailBaseOptions;
+    use crate::Result;
+
+    #[tokio::test]
+    async fn records_sent_emails() -> Result<()> {
+        let mock = MockClient::new();
+
+        let email = CreateEmailBaseOptions::new(
+            "Acme <onboarding@resend.dev>",
+            ["delivered@resend.dev"],
+            "Hello World!",
+        )
+        .with_text("Hello World!");
+
+        let id = mock.send(email).await?.id;
+        assert_eq!(id.as_ref(), "00000000-0000-0000-0000-000000000001");
+
+        let sent = mock.sent();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].subject, "Hello World!");
+
+        let retrieved = mock.get(&id).await?;
+        assert_eq!(retrieved.text.as_deref(), Some("Hello World!"));
+        assert!(mock.get("unknown").await.is_err());
+
+        Ok(())
+    }
+}